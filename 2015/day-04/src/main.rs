@@ -1,26 +1,19 @@
-use base16ct::lower;
-use md5::{Digest, Md5};
 use std::fs::File;
 use std::io::prelude::*;
 use std::io::BufReader;
+use utils::hash::{starts_with_zeros, NonceSearch};
 
 fn main() {
     let input = load_input("input");
-    println!("Solution for part 1: {}", solve(&input, "00000"));
-    println!("Solution for part 2: {}", solve(&input, "000000"));
+    println!("Solution for part 1: {}", solve(&input, 5));
+    println!("Solution for part 2: {}", solve(&input, 6));
 }
 
-fn solve(key: &str, start: &str) -> usize {
-    let mut hasher = Md5::new_with_prefix(key);
-    for digits in 0.. {
-        hasher.update(format!("{}", digits));
-        let hash = lower::encode_string(&hasher.finalize_reset());
-        if hash.starts_with(start) {
-            return digits;
-        }
-        hasher.update(key);
-    }
-    unreachable!()
+fn solve(key: &str, zeros: usize) -> u64 {
+    NonceSearch::new(key, |digest| starts_with_zeros(digest, zeros))
+        .next()
+        .unwrap()
+        .0
 }
 
 fn load_input(name: &str) -> String {
@@ -35,7 +28,7 @@ mod tests {
 
     #[test]
     fn part_1_test() {
-        assert_eq!(solve("abcdef", "00000"), 609043);
-        assert_eq!(solve("pqrstuv", "00000"), 1048970);
+        assert_eq!(solve("abcdef", 5), 609043);
+        assert_eq!(solve("pqrstuv", 5), 1048970);
     }
 }
@@ -3,6 +3,8 @@ use std::fs::File;
 use std::io::prelude::*;
 use std::io::BufReader;
 
+use utils::tsp::{held_karp, Goal, Route};
+
 #[derive(Debug)]
 struct Connection {
     target: String,
@@ -16,35 +18,23 @@ fn main() {
 }
 
 fn solve(conns: &HashMap<String, Vec<Connection>>, max: bool) -> usize {
-    // Just goes over every possibility since the input is short
-    let mut q: Vec<(Vec<&str>, usize)> = vec![];
-    let mut record = if max { 0 } else { usize::MAX };
-    for start in conns.keys() {
-        q.push((vec![start], 0));
-    }
-    while let Some((visited, cur_distance)) = q.pop() {
-        let cur = visited.last().unwrap().to_owned();
-        let mut moved = false;
-
-        for conn in conns.get(cur).unwrap() {
-            if !visited.contains(&conn.target.as_str()) {
-                let new_distance = cur_distance + conn.distance;
-                let mut new_visited = visited.clone();
-                new_visited.push(&conn.target);
-                q.push((new_visited, new_distance));
-                moved = true;
-            }
-        }
-        if !moved {
-            // No more valid targets so it's the end
-            if max {
-                record = record.max(cur_distance);
-            } else {
-                record = record.min(cur_distance);
-            }
-        }
-    }
-    record
+    let names: Vec<&String> = conns.keys().collect();
+    let distances: Vec<Vec<usize>> = names
+        .iter()
+        .map(|from| {
+            names
+                .iter()
+                .map(|to| {
+                    conns[*from]
+                        .iter()
+                        .find(|conn| &conn.target == *to)
+                        .map_or(0, |conn| conn.distance)
+                })
+                .collect()
+        })
+        .collect();
+    let goal = if max { Goal::Max } else { Goal::Min };
+    held_karp(&distances, goal, Route::Path, None).unwrap().0
 }
 
 fn load_input(name: &str) -> HashMap<String, Vec<Connection>> {
@@ -4,6 +4,7 @@ use std::io::prelude::*;
 use std::io::BufReader;
 
 use regex::Regex;
+use utils::tsp::{held_karp, Goal, Route};
 
 type Connection = HashMap<String, isize>;
 
@@ -23,33 +24,27 @@ fn add_yourself(map: &mut HashMap<String, Connection>) {
     map.insert(String::from("Yourself"), yourself);
 }
 
+/// Everyone sits at a round table so it's a tour and the happiness goes both ways.
 fn solve(map: &HashMap<String, Connection>) -> isize {
-    let names: Vec<String> = map.keys().map(|k| k.to_owned()).collect();
-    let mut highest = isize::MIN;
-    let mut q: Vec<Vec<String>> = names.iter().map(|k| vec![k.clone()]).collect();
-
-    while let Some(mut cur) = q.pop() {
-        if cur.len() == names.len() {
-            let mut local_count = 0;
-            for _ in 0..names.len() {
-                let first = &cur[0];
-                let second = &cur[1];
-                local_count += map[first][second];
-                local_count += map[second][first];
-                cur.rotate_left(1);
-            }
-            highest = highest.max(local_count);
-        }
-        for n in names.iter() {
-            if !cur.contains(n) {
-                let mut new = cur.clone();
-                new.push(n.clone());
-                q.push(new);
-            }
-        }
-    }
-
-    highest
+    let names: Vec<&String> = map.keys().collect();
+    let happiness: Vec<Vec<isize>> = names
+        .iter()
+        .map(|first| {
+            names
+                .iter()
+                .map(|second| {
+                    if first == second {
+                        0
+                    } else {
+                        map[*first][*second] + map[*second][*first]
+                    }
+                })
+                .collect()
+        })
+        .collect();
+    held_karp(&happiness, Goal::Max, Route::Tour, None)
+        .unwrap()
+        .0
 }
 
 fn load_input(name: &str) -> HashMap<String, Connection> {
@@ -4,51 +4,139 @@ use std::fs::File;
 use std::io::prelude::*;
 use std::io::BufReader;
 
-type Rules = HashMap<String, Vec<String>>;
+/// A molecule split into its elements
+type Molecule = Vec<String>;
+type Rules = HashMap<String, Vec<Molecule>>;
 
 fn main() {
-    let (rules, start) = load_input("input");
-    println!("Solution for part 1: {}", part_1(&rules, &start));
-    // println!("Solution for part 2: {}", part_2(&rules, &start));
+    let (rules, medicine) = load_input("input");
+    println!("Solution for part 1: {}", part_1(&rules, &medicine));
+    println!("Solution for part 2: {}", part_2(&rules, &medicine));
 }
 
-fn part_1(rules: &Rules, start: &str) -> usize {
-    let combinations: HashSet<String> = HashSet::from_iter(make_replacements(rules, start));
+fn part_1(rules: &Rules, molecule: &[String]) -> usize {
+    let combinations: HashSet<Molecule> = HashSet::from_iter(make_replacements(rules, molecule));
     combinations.len()
 }
 
+/// Uses the formula if the rules allow it and searches otherwise.
+fn part_2(rules: &Rules, molecule: &[String]) -> usize {
+    if follows_grammar(rules) {
+        count_steps(molecule)
+    } else {
+        reduce(rules, molecule, 0x2015_1219).expect("Molecule can't be made")
+    }
+}
 
-fn make_replacements(rules: &Rules, string: &str) -> Vec<String> {
-    let mut results: Vec<String> = Vec::new();
-    let mut s_iter = string.chars().enumerate().peekable();
-    while let Some((i, cur)) = s_iter.next() {
-        // Single char
-        if let Some(replacments) = rules.get(&cur.to_string()) {
-            for repl in replacments {
-                let new_s = format!("{}{}{}", &string[..i], repl, &string[i + 1..]);
-                results.push(new_s);
-            }
+/// Splits a molecule into elements, an element is an uppercase letter followed by lowercase ones.
+/// `e` is only ever on its own.
+fn tokenize(s: &str) -> Molecule {
+    let mut elements: Molecule = Vec::new();
+    for c in s.chars() {
+        match elements.last_mut() {
+            Some(last) if c.is_ascii_lowercase() => last.push(c),
+            _ => elements.push(c.to_string()),
         }
+    }
+    elements
+}
 
-        // Double char
-        if let Some((n_i, next)) = s_iter.peek() {
-            if let Some(replacments) = rules.get(&format!("{}{}", cur, next)) {
-                for repl in replacments {
-                    let new_s = format!("{}{}{}", &string[..i], repl, &string[n_i + 1..]);
-                    results.push(new_s);
-                }
-                // Skip the next char since it was used already
-                s_iter.next();
-            }
+fn make_replacements(rules: &Rules, molecule: &[String]) -> Vec<Molecule> {
+    let mut results: Vec<Molecule> = Vec::new();
+    for (i, element) in molecule.iter().enumerate() {
+        for repl in rules.get(element).into_iter().flatten() {
+            let mut new = molecule[..i].to_vec();
+            new.extend_from_slice(repl);
+            new.extend_from_slice(&molecule[i + 1..]);
+            results.push(new);
         }
     }
     results
 }
 
-fn load_input(name: &str) -> (Rules, String) {
+/// Checks if every rule turns an element into 2 elements or into `X Rn .. Ar` with the parts
+/// inside separated by `Y`.
+///
+/// `Rn`, `Y` and `Ar` never get replaced so every step adds one element, except the `Rn` ones that
+/// also add `Rn`, `Ar` and 2 elements for every `Y`.
+fn follows_grammar(rules: &Rules) -> bool {
+    let terminal = |e: &String| matches!(e.as_str(), "Rn" | "Y" | "Ar");
+    if rules.keys().any(terminal) {
+        return false;
+    }
+    rules.values().flatten().all(|repl| match repl.as_slice() {
+        [a, b] => !terminal(a) && !terminal(b),
+        [a, rn, inner @ .., ar] if rn == "Rn" && ar == "Ar" => {
+            !terminal(a)
+                && inner.len() % 2 == 1
+                && inner
+                    .iter()
+                    .enumerate()
+                    .all(|(i, e)| (i % 2 == 1) == (e == "Y") && (e == "Y" || !terminal(e)))
+        }
+        _ => false,
+    })
+}
+
+/// The amount of steps needed if the rules follow [`follows_grammar()`].
+fn count_steps(molecule: &[String]) -> usize {
+    let count = |name: &str| molecule.iter().filter(|e| *e == name).count();
+    molecule.len() - count("Rn") - count("Ar") - 2 * count("Y") - 1
+}
+
+/// Finds how many steps it takes to make the molecule from `e` by replacing parts of it with the
+/// element that makes them until only `e` is left.
+///
+/// Always takes the first rule that fits in a random order, if that ends up stuck it starts
+/// again with a different order. Gives up after 1000 tries.
+fn reduce(rules: &Rules, molecule: &[String], seed: u64) -> Option<usize> {
+    let mut reverse: Vec<(&Molecule, &String)> = rules
+        .iter()
+        .flat_map(|(from, repls)| repls.iter().map(move |repl| (repl, from)))
+        .collect();
+    reverse.sort();
+    let mut seed = seed.max(1);
+    let mut random = || {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        seed
+    };
+
+    for _ in 0..1000 {
+        // Fisher-Yates shuffle
+        for i in (1..reverse.len()).rev() {
+            reverse.swap(i, random() as usize % (i + 1));
+        }
+        let mut cur = molecule.to_vec();
+        let mut steps = 0;
+        while cur != ["e"] {
+            let found = reverse.iter().find_map(|(repl, from)| {
+                if *from == "e" {
+                    // Only the whole molecule can turn back into `e`
+                    return (cur == **repl).then_some((0, repl.len(), *from));
+                }
+                cur.windows(repl.len())
+                    .position(|w| w == repl.as_slice())
+                    .map(|i| (i, repl.len(), *from))
+            });
+            let Some((i, len, from)) = found else {
+                break;
+            };
+            cur.splice(i..i + len, [from.clone()]);
+            steps += 1;
+        }
+        if cur == ["e"] {
+            return Some(steps);
+        }
+    }
+    None
+}
+
+fn load_input(name: &str) -> (Rules, Molecule) {
     let file = File::open(name).unwrap_or_else(|_| panic!("No \"{}\" file found", name));
     let reader = BufReader::new(file);
-    let mut rules: HashMap<String, Vec<String>> = HashMap::new();
+    let mut rules: Rules = HashMap::new();
     let mut lines = reader.lines().map(|l| l.unwrap());
     loop {
         let line = lines.next().unwrap();
@@ -56,9 +144,9 @@ fn load_input(name: &str) -> (Rules, String) {
             break;
         }
         let (to, from) = line.split_once(" => ").unwrap();
-        (*rules.entry(to.to_owned()).or_default()).push(from.to_owned());
+        (*rules.entry(to.to_owned()).or_default()).push(tokenize(from));
     }
-    (rules, lines.next().unwrap())
+    (rules, tokenize(&lines.next().unwrap()))
 }
 
 #[cfg(test)]
@@ -68,28 +156,106 @@ mod tests {
 
     fn example_rules() -> Rules {
         let mut rules: Rules = HashMap::new();
-        rules.insert("e".to_owned(), vec!["H".to_owned(), "O".to_owned()]);
-        rules.insert("H".to_owned(), vec!["HO".to_owned(), "OH".to_owned()]);
-        rules.insert("O".to_owned(), vec!["HH".to_owned()]);
+        for (from, to) in [
+            ("e", "H"),
+            ("e", "O"),
+            ("H", "HO"),
+            ("H", "OH"),
+            ("O", "HH"),
+        ] {
+            rules.entry(from.to_owned()).or_default().push(tokenize(to));
+        }
         rules
     }
 
+    #[test]
+    fn tokenize_test() {
+        assert_eq!(tokenize("e"), ["e"]);
+        assert_eq!(tokenize("HOH"), ["H", "O", "H"]);
+        assert_eq!(
+            tokenize("CRnSiRnCaYFAr"),
+            ["C", "Rn", "Si", "Rn", "Ca", "Y", "F", "Ar"]
+        );
+    }
+
     #[test]
     fn part_1_example_1() {
         let rules = example_rules();
-        assert_eq!(part_1(&rules, "HOH"), 4);
+        assert_eq!(part_1(&rules, &tokenize("HOH")), 4);
     }
 
     #[test]
     fn part_1_example_2() {
         let rules = example_rules();
-        assert_eq!(part_1(&rules, "HOHOHO"), 7);
+        assert_eq!(part_1(&rules, &tokenize("HOHOHO")), 7);
+    }
+
+    #[test]
+    fn part_2_test() {
+        let rules = example_rules();
+        assert!(!follows_grammar(&rules));
+        assert_eq!(part_2(&rules, &tokenize("HOH")), 3);
+        assert_eq!(part_2(&rules, &tokenize("HOHOHO")), 6);
     }
 
-    // #[test]
-    // fn part_2_test() {
-    //     let rules = example_rules();
-    //     assert_eq!(part_2(&rules, "HOH"), 3);
-    //     assert_eq!(part_2(&rules, "HOHOHO"), 7);
-    // }
+    /// Rules shaped like the real input, molecules are made by applying random rules to `e` and
+    /// both ways of solving have to find the amount of rules used.
+    #[test]
+    fn part_2_grammar() {
+        let mut rules: Rules = HashMap::new();
+        for line in [
+            "e => HF",
+            "e => NAl",
+            "e => OMg",
+            "H => HCa",
+            "H => NTh",
+            "H => CRnAlAr",
+            "H => CRnFYFYFAr",
+            "F => CaF",
+            "F => PMg",
+            "F => SiAl",
+            "Al => ThF",
+            "Al => ThRnFAr",
+            "Ca => CaCa",
+            "Ca => PB",
+            "Ca => SiRnFYFAr",
+            "N => CRnFAr",
+            "N => HSi",
+            "O => HP",
+            "O => NRnFAr",
+            "Mg => BF",
+            "Th => ThCa",
+            "P => CaP",
+            "P => PTi",
+            "B => BCa",
+            "Si => CaSi",
+            "Ti => BP",
+        ] {
+            let (from, to) = line.split_once(" => ").unwrap();
+            rules.entry(from.to_owned()).or_default().push(tokenize(to));
+        }
+        assert!(follows_grammar(&rules));
+
+        let mut seed: u64 = 19;
+        let mut random = || {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed as usize
+        };
+        for steps in 1..40 {
+            let mut molecule = tokenize("e");
+            for _ in 0..steps {
+                let options = make_replacements(&rules, &molecule);
+                molecule = options[random() % options.len()].clone();
+            }
+            assert_eq!(count_steps(&molecule), steps, "{}", molecule.concat());
+            assert_eq!(
+                reduce(&rules, &molecule, 1),
+                Some(steps),
+                "{}",
+                molecule.concat()
+            );
+        }
+    }
 }
@@ -1,3 +1,86 @@
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::BufReader;
+
 fn main() {
-    println!("Hello, world!");
+    let input = load_input("input");
+    println!("Solution for part 1: {}", part_1(input));
+    println!("Solution for part 2: {}", part_2(input));
+}
+
+fn part_1(target: usize) -> usize {
+    // Elf n alone brings 10 * n presents to house n so there is no need to look further
+    first_house(&presents(target / 10 + 1, 10, None), target)
+}
+
+fn part_2(target: usize) -> usize {
+    first_house(&presents(target / 11 + 1, 11, Some(50)), target)
+}
+
+fn first_house(houses: &[usize], target: usize) -> usize {
+    houses.iter().position(|&p| p >= target).unwrap()
+}
+
+/// Sieve of how many presents every house up to `limit` gets.
+///
+/// Every elf gives `per_house` times its number to every house it visits and stops after
+/// `max_houses` houses if there is a limit.
+fn presents(limit: usize, per_house: usize, max_houses: Option<usize>) -> Vec<usize> {
+    let mut houses = vec![0; limit + 1];
+    for elf in 1..=limit {
+        let last = match max_houses {
+            Some(max) => limit.min(elf * max),
+            None => limit,
+        };
+        for house in (elf..=last).step_by(elf) {
+            houses[house] += elf * per_house;
+        }
+    }
+    houses
+}
+
+fn load_input(name: &str) -> usize {
+    let file = File::open(name).unwrap_or_else(|_| panic!("No \"{}\" file found", name));
+    let reader = BufReader::new(file);
+    reader
+        .lines()
+        .next()
+        .unwrap()
+        .unwrap()
+        .trim()
+        .parse()
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presents_test() {
+        assert_eq!(
+            presents(9, 10, None)[1..],
+            [10, 30, 40, 70, 60, 120, 80, 150, 130]
+        );
+        // Elf 1 already stopped and elf 3 stops at this house
+        assert_eq!(presents(150, 11, Some(50))[51], 11 * (3 + 17 + 51));
+        assert_eq!(
+            presents(150, 11, Some(50))[150],
+            11 * (3 + 5 + 6 + 10 + 15 + 25 + 30 + 50 + 75 + 150)
+        );
+    }
+
+    #[test]
+    fn part_1_test() {
+        assert_eq!(part_1(70), 4);
+        assert_eq!(part_1(120), 6);
+        assert_eq!(part_1(130), 8);
+        assert_eq!(part_1(150), 8);
+    }
+
+    #[test]
+    fn part_2_test() {
+        assert_eq!(part_2(11), 1);
+        assert_eq!(part_2(132), 6);
+    }
 }
@@ -1,3 +1,159 @@
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::BufReader;
+
+/// (cost, damage, armor)
+type Item = (usize, i64, i64);
+
+const WEAPONS: [Item; 5] = [(8, 4, 0), (10, 5, 0), (25, 6, 0), (40, 7, 0), (74, 8, 0)];
+const ARMOR: [Item; 5] = [(13, 0, 1), (31, 0, 2), (53, 0, 3), (75, 0, 4), (102, 0, 5)];
+const RINGS: [Item; 6] = [
+    (25, 1, 0),
+    (50, 2, 0),
+    (100, 3, 0),
+    (20, 0, 1),
+    (40, 0, 2),
+    (80, 0, 3),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Character {
+    hit_points: i64,
+    damage: i64,
+    armor: i64,
+}
+
+impl Character {
+    /// The player goes first so they win ties.
+    fn beats(&self, other: &Character) -> bool {
+        let turns_to_win = |attacker: &Character, defender: &Character| {
+            let damage = (attacker.damage - defender.armor).max(1);
+            (defender.hit_points + damage - 1) / damage
+        };
+        turns_to_win(self, other) <= turns_to_win(other, self)
+    }
+}
+
 fn main() {
-    println!("Hello, world!");
+    let input = load_input("input");
+    println!("Solution for part 1: {}", part_1(&input));
+    println!("Solution for part 2: {}", part_2(&input));
+}
+
+fn part_1(boss: &Character) -> usize {
+    loadouts()
+        .into_iter()
+        .filter(|(_, player)| player.beats(boss))
+        .map(|(cost, _)| cost)
+        .min()
+        .unwrap()
+}
+
+fn part_2(boss: &Character) -> usize {
+    loadouts()
+        .into_iter()
+        .filter(|(_, player)| !player.beats(boss))
+        .map(|(cost, _)| cost)
+        .max()
+        .unwrap()
+}
+
+/// Every way to buy 1 weapon, up to 1 armor and up to 2 different rings, with the cost.
+fn loadouts() -> Vec<(usize, Character)> {
+    let armors: Vec<Option<Item>> = [None].into_iter().chain(ARMOR.map(Some)).collect();
+    let mut ring_pairs: Vec<Vec<Item>> = vec![vec![]];
+    for (i, first) in RINGS.iter().enumerate() {
+        ring_pairs.push(vec![*first]);
+        for second in &RINGS[i + 1..] {
+            ring_pairs.push(vec![*first, *second]);
+        }
+    }
+
+    let mut loadouts = Vec::new();
+    for weapon in WEAPONS {
+        for armor in &armors {
+            for rings in &ring_pairs {
+                let items = [weapon].into_iter().chain(*armor).chain(rings.clone());
+                let (mut cost, mut player) = (
+                    0,
+                    Character {
+                        hit_points: 100,
+                        damage: 0,
+                        armor: 0,
+                    },
+                );
+                for (c, damage, armor) in items {
+                    cost += c;
+                    player.damage += damage;
+                    player.armor += armor;
+                }
+                loadouts.push((cost, player));
+            }
+        }
+    }
+    loadouts
+}
+
+fn load_input(name: &str) -> Character {
+    let file = File::open(name).unwrap_or_else(|_| panic!("No \"{}\" file found", name));
+    let reader = BufReader::new(file);
+    let stats: Vec<i64> = reader
+        .lines()
+        .map(|l| l.unwrap().split_once(": ").unwrap().1.parse().unwrap())
+        .collect();
+    Character {
+        hit_points: stats[0],
+        damage: stats[1],
+        armor: stats[2],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fight_test() {
+        let player = Character {
+            hit_points: 8,
+            damage: 5,
+            armor: 5,
+        };
+        let boss = Character {
+            hit_points: 12,
+            damage: 7,
+            armor: 2,
+        };
+        assert!(player.beats(&boss));
+        assert!(!Character {
+            hit_points: 6,
+            ..player
+        }
+        .beats(&boss));
+    }
+
+    #[test]
+    fn loadouts_test() {
+        // 5 weapons * 6 armor options * (1 + 6 + 15) ring options
+        assert_eq!(loadouts().len(), 5 * 6 * 22);
+        assert_eq!(loadouts().iter().map(|l| l.0).min(), Some(8));
+    }
+
+    #[test]
+    fn parts_test() {
+        // Only needs the cheapest weapon because of the first move
+        let weak = Character {
+            hit_points: 1,
+            damage: 100,
+            armor: 0,
+        };
+        assert_eq!(part_1(&weak), 8);
+        // Nothing wins so the most expensive loadout is the answer
+        let strong = Character {
+            hit_points: 1000,
+            damage: 1000,
+            armor: 0,
+        };
+        assert_eq!(part_2(&strong), 74 + 102 + 100 + 80);
+    }
 }
@@ -1,3 +1,254 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::BufReader;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Boss {
+    hit_points: i64,
+    damage: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+enum Spell {
+    MagicMissile,
+    Drain,
+    Shield,
+    Poison,
+    Recharge,
+}
+
+impl Spell {
+    const ALL: [Spell; 5] = [
+        Spell::MagicMissile,
+        Spell::Drain,
+        Spell::Shield,
+        Spell::Poison,
+        Spell::Recharge,
+    ];
+
+    fn cost(&self) -> i64 {
+        match self {
+            Spell::MagicMissile => 53,
+            Spell::Drain => 73,
+            Spell::Shield => 113,
+            Spell::Poison => 173,
+            Spell::Recharge => 229,
+        }
+    }
+}
+
+/// How a round ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    /// With how much mana was spent in the round
+    Won(i64),
+    Lost,
+    Continue(State),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+struct State {
+    hit_points: i64,
+    mana: i64,
+    boss_hit_points: i64,
+    shield: u8,
+    poison: u8,
+    recharge: u8,
+}
+
+impl State {
+    fn new(hit_points: i64, mana: i64, boss: &Boss) -> Self {
+        Self {
+            hit_points,
+            mana,
+            boss_hit_points: boss.hit_points,
+            shield: 0,
+            poison: 0,
+            recharge: 0,
+        }
+    }
+
+    /// Applies the active effects and returns the armor for the turn.
+    fn apply_effects(&mut self) -> i64 {
+        let armor = if self.shield > 0 { 7 } else { 0 };
+        if self.poison > 0 {
+            self.boss_hit_points -= 3;
+        }
+        if self.recharge > 0 {
+            self.mana += 101;
+        }
+        self.shield = self.shield.saturating_sub(1);
+        self.poison = self.poison.saturating_sub(1);
+        self.recharge = self.recharge.saturating_sub(1);
+        armor
+    }
+
+    /// Plays the player's turn with the spell and then the boss's turn.
+    ///
+    /// `None` if the spell can't be cast. In hard mode the player loses 1 hit point at the start
+    /// of their turn.
+    fn round(mut self, spell: Spell, boss: &Boss, hard: bool) -> Option<Outcome> {
+        if hard {
+            self.hit_points -= 1;
+            if self.hit_points <= 0 {
+                return Some(Outcome::Lost);
+            }
+        }
+        self.apply_effects();
+        if self.boss_hit_points <= 0 {
+            return Some(Outcome::Won(0));
+        }
+
+        if self.mana < spell.cost() {
+            return None;
+        }
+        self.mana -= spell.cost();
+        match spell {
+            Spell::MagicMissile => self.boss_hit_points -= 4,
+            Spell::Drain => {
+                self.boss_hit_points -= 2;
+                self.hit_points += 2;
+            }
+            Spell::Shield if self.shield == 0 => self.shield = 6,
+            Spell::Poison if self.poison == 0 => self.poison = 6,
+            Spell::Recharge if self.recharge == 0 => self.recharge = 5,
+            // The effect is still active
+            _ => return None,
+        }
+        if self.boss_hit_points <= 0 {
+            return Some(Outcome::Won(spell.cost()));
+        }
+
+        let armor = self.apply_effects();
+        if self.boss_hit_points <= 0 {
+            return Some(Outcome::Won(spell.cost()));
+        }
+        self.hit_points -= (boss.damage - armor).max(1);
+        if self.hit_points <= 0 {
+            return Some(Outcome::Lost);
+        }
+        Some(Outcome::Continue(self))
+    }
+}
+
 fn main() {
-    println!("Hello, world!");
+    let input = load_input("input");
+    println!(
+        "Solution for part 1: {}",
+        least_mana(&input, 50, 500, false)
+    );
+    println!("Solution for part 2: {}", least_mana(&input, 50, 500, true));
+}
+
+/// Dijkstra over the fight states with the spent mana as the distance, `None` is a won fight.
+fn least_mana(boss: &Boss, hit_points: i64, mana: i64, hard: bool) -> i64 {
+    let start = State::new(hit_points, mana, boss);
+    let mut queue = BinaryHeap::from([Reverse((0, Some(start)))]);
+    let mut seen: HashSet<State> = HashSet::new();
+    while let Some(Reverse((spent, state))) = queue.pop() {
+        let Some(state) = state else {
+            return spent;
+        };
+        if !seen.insert(state) {
+            continue;
+        }
+        for spell in Spell::ALL {
+            match state.round(spell, boss, hard) {
+                Some(Outcome::Won(cost)) => queue.push(Reverse((spent + cost, None))),
+                Some(Outcome::Continue(next)) => {
+                    queue.push(Reverse((spent + spell.cost(), Some(next))))
+                }
+                Some(Outcome::Lost) | None => {}
+            }
+        }
+    }
+    panic!("The boss can't be beaten")
+}
+
+fn load_input(name: &str) -> Boss {
+    let file = File::open(name).unwrap_or_else(|_| panic!("No \"{}\" file found", name));
+    let reader = BufReader::new(file);
+    let stats: Vec<i64> = reader
+        .lines()
+        .map(|l| l.unwrap().split_once(": ").unwrap().1.parse().unwrap())
+        .collect();
+    Boss {
+        hit_points: stats[0],
+        damage: stats[1],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Casts the spells one after another, returns the mana spent if the player won.
+    fn play(boss: &Boss, spells: &[Spell]) -> Option<i64> {
+        let mut state = State::new(10, 250, boss);
+        let mut spent = 0;
+        for spell in spells {
+            match state.round(*spell, boss, false)? {
+                Outcome::Won(cost) => return Some(spent + cost),
+                Outcome::Lost => return None,
+                Outcome::Continue(next) => {
+                    spent += spell.cost();
+                    state = next;
+                }
+            }
+        }
+        None
+    }
+
+    #[test]
+    fn examples() {
+        let boss = Boss {
+            hit_points: 13,
+            damage: 8,
+        };
+        assert_eq!(
+            play(&boss, &[Spell::Poison, Spell::MagicMissile]),
+            Some(226)
+        );
+        assert_eq!(least_mana(&boss, 10, 250, false), 226);
+
+        let boss = Boss {
+            hit_points: 14,
+            damage: 8,
+        };
+        let spells = [
+            Spell::Recharge,
+            Spell::Shield,
+            Spell::Drain,
+            Spell::Poison,
+            Spell::MagicMissile,
+        ];
+        assert_eq!(play(&boss, &spells), Some(641));
+        assert!(least_mana(&boss, 10, 250, false) <= 641);
+    }
+
+    #[test]
+    fn effects() {
+        let boss = Boss {
+            hit_points: 14,
+            damage: 8,
+        };
+        let state = State::new(10, 250, &boss);
+        // Can't cast something that is still active
+        let Some(Outcome::Continue(state)) = state.round(Spell::Shield, &boss, false) else {
+            panic!()
+        };
+        assert_eq!(state.hit_points, 9);
+        assert_eq!(state.round(Spell::Shield, &boss, false), None);
+        // Too expensive
+        assert_eq!(state.round(Spell::Recharge, &boss, false), None);
+        // Hard mode
+        let state = State::new(1, 250, &boss);
+        assert_eq!(
+            state.round(Spell::MagicMissile, &boss, true),
+            Some(Outcome::Lost)
+        );
+    }
 }
@@ -1,3 +1,148 @@
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::BufReader;
+use std::str::FromStr;
+
+use utils::vm::{Flow, Instruction, Machine, Registers};
+
 fn main() {
-    println!("Hello, world!");
+    let input = load_input("input");
+    println!("Solution for part 1: {}", run(&input, 0)['b']);
+    println!("Solution for part 2: {}", run(&input, 1)['b']);
+}
+
+/// Runs the program with `a` starting at the given value and returns the registers at the end.
+fn run(program: &[Op], a: i64) -> Registers {
+    let mut machine = Machine::new(program.to_vec(), 2);
+    machine.registers['a'] = a;
+    machine.run();
+    machine.registers
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Hlf(char),
+    Tpl(char),
+    Inc(char),
+    Jmp(isize),
+    /// Jump if even
+    Jie(char, isize),
+    /// Jump if one
+    Jio(char, isize),
+}
+
+impl Instruction for Op {
+    fn execute(&self, machine: &mut Machine<Self>) -> Flow {
+        match *self {
+            Op::Hlf(r) => machine.registers[r] /= 2,
+            Op::Tpl(r) => machine.registers[r] *= 3,
+            Op::Inc(r) => machine.registers[r] += 1,
+            Op::Jmp(offset) => return Flow::Jump(offset),
+            Op::Jie(r, offset) => {
+                if machine.registers[r] % 2 == 0 {
+                    return Flow::Jump(offset);
+                }
+            }
+            Op::Jio(r, offset) => {
+                if machine.registers[r] == 1 {
+                    return Flow::Jump(offset);
+                }
+            }
+        }
+        Flow::Next
+    }
+}
+
+impl FromStr for Op {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, args) = s.split_once(' ').ok_or(())?;
+        let register = |arg: &str| match arg {
+            "a" | "b" => Ok(arg.chars().next().unwrap()),
+            _ => Err(()),
+        };
+        let offset = |arg: &str| arg.parse::<isize>().map_err(|_| ());
+        Ok(match name {
+            "hlf" => Op::Hlf(register(args)?),
+            "tpl" => Op::Tpl(register(args)?),
+            "inc" => Op::Inc(register(args)?),
+            "jmp" => Op::Jmp(offset(args)?),
+            "jie" | "jio" => {
+                let (r, o) = args.split_once(", ").ok_or(())?;
+                if name == "jie" {
+                    Op::Jie(register(r)?, offset(o)?)
+                } else {
+                    Op::Jio(register(r)?, offset(o)?)
+                }
+            }
+            _ => return Err(()),
+        })
+    }
+}
+
+fn load_input(name: &str) -> Vec<Op> {
+    let file = File::open(name).unwrap_or_else(|_| panic!("No \"{}\" file found", name));
+    let reader = BufReader::new(file);
+    reader
+        .lines()
+        .map(|l| l.unwrap().parse().unwrap())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(lines: &[&str]) -> Vec<Op> {
+        lines.iter().map(|l| l.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn parse_test() {
+        assert_eq!(
+            parse(&[
+                "hlf a",
+                "tpl b",
+                "inc a",
+                "jmp -7",
+                "jie b, +4",
+                "jio a, +2"
+            ]),
+            [
+                Op::Hlf('a'),
+                Op::Tpl('b'),
+                Op::Inc('a'),
+                Op::Jmp(-7),
+                Op::Jie('b', 4),
+                Op::Jio('a', 2)
+            ]
+        );
+        assert_eq!("inc c".parse::<Op>(), Err(()));
+    }
+
+    #[test]
+    fn example() {
+        let program = parse(&["inc a", "jio a, +2", "tpl a", "inc a"]);
+        assert_eq!(run(&program, 0)['a'], 2);
+        assert_eq!(run(&program, 1)['a'], 7);
+    }
+
+    #[test]
+    fn collatz() {
+        // Counts the steps of the Collatz sequence of a in b
+        let program = parse(&[
+            "jio a, +8",
+            "inc b",
+            "jie a, +4",
+            "tpl a",
+            "inc a",
+            "jmp -5",
+            "hlf a",
+            "jmp -7",
+        ]);
+        assert_eq!(run(&program, 1)['b'], 0);
+        assert_eq!(run(&program, 6)['b'], 8);
+        assert_eq!(run(&program, 27)['b'], 111);
+    }
 }
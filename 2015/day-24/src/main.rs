@@ -1,3 +1,133 @@
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::BufReader;
+
 fn main() {
-    println!("Hello, world!");
+    let input = load_input("input");
+    println!("Solution for part 1: {}", solve(&input, 3));
+    println!("Solution for part 2: {}", solve(&input, 4));
+}
+
+/// Finds the smallest group for the passenger compartment that still lets the rest be split
+/// evenly, ties are broken by the quantum entanglement.
+fn solve(weights: &[u64], groups: u64) -> u64 {
+    let total: u64 = weights.iter().sum();
+    assert_eq!(total % groups, 0, "Weights can't be split evenly");
+    let target = total / groups;
+
+    for size in 1..=weights.len() {
+        let mut candidates: Vec<Vec<usize>> = Vec::new();
+        combinations(weights, size, target, 0, &mut vec![], &mut candidates);
+        let entanglement = |group: &Vec<usize>| group.iter().map(|&i| weights[i]).product::<u64>();
+        candidates.sort_by_key(entanglement);
+        for group in candidates {
+            let rest: Vec<u64> = (0..weights.len())
+                .filter(|i| !group.contains(i))
+                .map(|i| weights[i])
+                .collect();
+            if can_split(&rest, groups - 1, target) {
+                return entanglement(&group);
+            }
+        }
+    }
+    panic!("No way to split the weights")
+}
+
+/// Every set of `size` packages starting from `start` that weigh `target` together.
+fn combinations(
+    weights: &[u64],
+    size: usize,
+    target: u64,
+    start: usize,
+    cur: &mut Vec<usize>,
+    found: &mut Vec<Vec<usize>>,
+) {
+    if cur.len() == size {
+        if target == 0 {
+            found.push(cur.clone());
+        }
+        return;
+    }
+    for i in start..weights.len() {
+        if weights[i] <= target {
+            cur.push(i);
+            combinations(weights, size, target - weights[i], i + 1, cur, found);
+            cur.pop();
+        }
+    }
+}
+
+/// Checks if the weights can be split into `groups` groups that all weigh `target`.
+fn can_split(weights: &[u64], groups: u64, target: u64) -> bool {
+    if groups <= 1 {
+        return weights.iter().sum::<u64>() == target * groups;
+    }
+    fn find(
+        weights: &[u64],
+        used: &mut [bool],
+        i: usize,
+        left: u64,
+        groups: u64,
+        target: u64,
+    ) -> bool {
+        if left == 0 {
+            let rest: Vec<u64> = (0..weights.len())
+                .filter(|&j| !used[j])
+                .map(|j| weights[j])
+                .collect();
+            return can_split(&rest, groups - 1, target);
+        }
+        for j in i..weights.len() {
+            if !used[j] && weights[j] <= left {
+                used[j] = true;
+                if find(weights, used, j + 1, left - weights[j], groups, target) {
+                    return true;
+                }
+                used[j] = false;
+            }
+        }
+        false
+    }
+    find(
+        weights,
+        &mut vec![false; weights.len()],
+        0,
+        target,
+        groups,
+        target,
+    )
+}
+
+fn load_input(name: &str) -> Vec<u64> {
+    let file = File::open(name).unwrap_or_else(|_| panic!("No \"{}\" file found", name));
+    let reader = BufReader::new(file);
+    reader
+        .lines()
+        .map(|l| l.unwrap().parse().unwrap())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: [u64; 10] = [1, 2, 3, 4, 5, 7, 8, 9, 10, 11];
+
+    #[test]
+    fn part_1_test() {
+        assert_eq!(solve(&EXAMPLE, 3), 99);
+    }
+
+    #[test]
+    fn part_2_test() {
+        assert_eq!(solve(&EXAMPLE, 4), 44);
+    }
+
+    #[test]
+    fn split_test() {
+        assert!(can_split(&[1, 2, 3, 4, 5, 5], 2, 10));
+        assert!(can_split(&[1, 2, 3, 4, 5, 5], 4, 5));
+        assert!(!can_split(&[1, 2, 3, 4, 5, 5], 5, 4));
+        assert!(!can_split(&[6, 6, 3, 1], 2, 8));
+    }
 }
@@ -1,3 +1,74 @@
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::BufReader;
+
+const FIRST_CODE: u64 = 20151125;
+const MULTIPLIER: u64 = 252533;
+const MODULUS: u64 = 33554393;
+
 fn main() {
-    println!("Hello, world!");
+    let (row, column) = load_input("input");
+    println!("Solution for part 1: {}", code_at(row, column));
+}
+
+/// Codes are filled in diagonally so the position in the order can be calculated directly and
+/// the code is the first one multiplied that many times.
+fn code_at(row: u64, column: u64) -> u64 {
+    let diagonal = row + column - 1;
+    let index = diagonal * (diagonal - 1) / 2 + column - 1;
+    FIRST_CODE * pow_mod(MULTIPLIER, index, MODULUS) % MODULUS
+}
+
+/// Exponentiation by squaring
+fn pow_mod(mut base: u64, mut exponent: u64, modulus: u64) -> u64 {
+    let mut result = 1;
+    base %= modulus;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result * base % modulus;
+        }
+        base = base * base % modulus;
+        exponent >>= 1;
+    }
+    result
+}
+
+/// Returns (row, column)
+fn load_input(name: &str) -> (u64, u64) {
+    let file = File::open(name).unwrap_or_else(|_| panic!("No \"{}\" file found", name));
+    let reader = BufReader::new(file);
+    let line = reader.lines().next().unwrap().unwrap();
+    let numbers: Vec<u64> = line
+        .split(|c: char| !c.is_ascii_digit())
+        .filter_map(|n| n.parse().ok())
+        .collect();
+    (numbers[0], numbers[1])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part_1_test() {
+        let table = [
+            [20151125, 18749137, 17289845, 30943339, 10071777, 33511524],
+            [31916031, 21629792, 16929656, 7726640, 15514188, 4041754],
+            [16080970, 8057251, 1601130, 7981243, 11661866, 16474243],
+            [24592653, 32451966, 21345942, 9380097, 10600672, 31527494],
+            [77061, 17552253, 28094349, 6899651, 9250759, 31663883],
+            [33071741, 6796745, 25397450, 24659492, 1534922, 27995004],
+        ];
+        for (row, codes) in table.iter().enumerate() {
+            for (column, code) in codes.iter().enumerate() {
+                assert_eq!(code_at(row as u64 + 1, column as u64 + 1), *code);
+            }
+        }
+    }
+
+    #[test]
+    fn pow_mod_test() {
+        assert_eq!(pow_mod(2, 10, 1000), 24);
+        assert_eq!(pow_mod(MULTIPLIER, 0, MODULUS), 1);
+    }
 }
@@ -2,7 +2,7 @@ use std::fs::File;
 use std::io::prelude::*;
 use std::io::BufReader;
 
-use md5::{Digest, Md5};
+use utils::hash::{nibble, starts_with_zeros, NonceSearch};
 
 fn main() {
     let input = load_input("input");
@@ -11,48 +11,33 @@ fn main() {
 }
 
 fn part_1(password: &str) -> String {
-    let mut solution: Vec<char> = vec![];
-    for i in 0.. {
-        let hash = base16ct::lower::encode_string(&Md5::digest(format!("{password}{i}")));
-        if hash.starts_with("00000") {
-            solution.push(hash.chars().nth(5).unwrap());
-            if solution.len() == 8 {
-                break;
-            }
-        }
-    }
-
-    solution.into_iter().collect()
+    NonceSearch::new(password, |digest| starts_with_zeros(digest, 5))
+        .take(8)
+        .map(|(_, digest)| hex_char(nibble(&digest, 5)))
+        .collect()
 }
 
 fn part_2(password: &str) -> String {
-    let mut solution: [char; 8] = ['x'; 8];
-    let mut counter = [false; 8];
-    for i in 0.. {
-        let hash = base16ct::lower::encode_string(&Md5::digest(format!("{password}{i}")));
-        if hash.starts_with("00000") {
-            let mut chars = hash.chars().skip(5);
-            let position = match chars.next().unwrap().to_digit(10) {
-                Some(n) => {
-                    if n >= 8 {
-                        continue;
-                    } else {
-                        n as usize
-                    }
-                }
-                None => continue,
-            };
-            if !counter[position] {
-                solution[position] = chars.next().unwrap();
-                counter[position] = true;
-            }
-            if counter.iter().all(|b| *b) {
-                break;
-            }
+    let mut solution: [Option<char>; 8] = [None; 8];
+    // Only the hashes with a valid position are interesting
+    let search = NonceSearch::new(password, |digest| {
+        starts_with_zeros(digest, 5) && nibble(digest, 5) < 8
+    });
+    for (_, digest) in search {
+        let position = nibble(&digest, 5) as usize;
+        if solution[position].is_none() {
+            solution[position] = Some(hex_char(nibble(&digest, 6)));
+        }
+        if solution.iter().all(|c| c.is_some()) {
+            break;
         }
     }
 
-    solution.into_iter().collect()
+    solution.into_iter().map(|c| c.unwrap()).collect()
+}
+
+fn hex_char(digit: u8) -> char {
+    char::from_digit(digit as u32, 16).unwrap()
 }
 
 fn load_input(name: &str) -> String {
@@ -60,3 +45,18 @@ fn load_input(name: &str) -> String {
     let reader = BufReader::new(file);
     reader.lines().next().unwrap().unwrap()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part_1_test() {
+        assert_eq!(part_1("abc"), "18f47a30");
+    }
+
+    #[test]
+    fn part_2_test() {
+        assert_eq!(part_2("abc"), "05ace8e3");
+    }
+}
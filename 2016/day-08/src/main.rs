@@ -7,11 +7,20 @@ type Screen = [[bool; 50]; 6];
 
 fn main() {
     let input = load_input("input");
-    println!("Solution for part 2:");
-    println!("Solution for part 1: {}", part_1(&input));
+    let screen = run(&input);
+    println!("Solution for part 1: {}", part_1(&screen));
+    println!("Solution for part 2: {}", part_2(&screen));
 }
 
-fn part_1(instructions: &[Instruction]) -> usize {
+fn part_1(screen: &Screen) -> usize {
+    screen.iter().flat_map(|r| r.iter()).filter(|x| **x).count()
+}
+
+fn part_2(screen: &Screen) -> String {
+    utils::ocr::recognise(screen)
+}
+
+fn run(instructions: &[Instruction]) -> Screen {
     let mut screen = [[false; 50]; 6];
 
     for &i in instructions {
@@ -33,26 +42,7 @@ fn part_1(instructions: &[Instruction]) -> usize {
             }
         }
     }
-    print_screen(&screen);
-
     screen
-        .into_iter()
-        .flat_map(|r| r.into_iter())
-        .filter(|x| *x)
-        .count()
-}
-
-fn print_screen(screen: &Screen) {
-    for row in screen {
-        for p in row {
-            match p {
-                true => print!("#"),
-                false => print!(" "),
-            }
-        }
-        println!();
-    }
-    println!()
 }
 
 fn rotate_column(grid: &mut Screen, column: usize, by: usize) {
@@ -96,3 +86,25 @@ impl From<String> for Instruction {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn draw_letter() {
+        let input: Vec<Instruction> = [
+            "rect 4x1",
+            "rotate column x=1 by 5",
+            "rotate column x=2 by 5",
+            "rotate column x=3 by 5",
+            "rect 1x6",
+        ]
+        .into_iter()
+        .map(|l| Instruction::from(l.to_string()))
+        .collect();
+        let screen = run(&input);
+        assert_eq!(part_1(&screen), 9);
+        assert_eq!(part_2(&screen), "L");
+    }
+}
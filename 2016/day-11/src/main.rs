@@ -1,3 +1,192 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs::File;
+use std::io::BufReader;
+
+use utils::BufReadExt;
+
+const FLOORS: u8 = 4;
+
 fn main() {
-    println!("Hello, world!");
+    let input = load_input("input");
+    println!("Solution for part 1: {}", part_1(&input));
+    println!("Solution for part 2: {}", part_2(&input));
+}
+
+fn part_1(start: &State) -> usize {
+    solve(start.clone())
+}
+
+fn part_2(start: &State) -> usize {
+    let mut start = start.clone();
+    // The elerium and dilithium generators and microchips
+    start.pairs.extend([(0, 0), (0, 0)]);
+    start.normalize();
+    solve(start)
+}
+
+/// Breadth-first search for the least amount of elevator rides needed to get everything to the
+/// top floor.
+fn solve(start: State) -> usize {
+    let mut seen = HashSet::from([start.clone()]);
+    let mut queue = VecDeque::from([(start, 0)]);
+    while let Some((state, steps)) = queue.pop_front() {
+        if state.is_done() {
+            return steps;
+        }
+        for next in state.moves() {
+            if seen.insert(next.clone()) {
+                queue.push_back((next, steps + 1));
+            }
+        }
+    }
+    panic!("No solution found")
+}
+
+/// The floors of the generator and microchip of every element.
+///
+/// Which element is which doesn't matter, only where the pairs are. The pairs are always kept
+/// sorted so states that only differ by swapping elements are the same.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct State {
+    elevator: u8,
+    /// (generator, microchip)
+    pairs: Vec<(u8, u8)>,
+}
+
+impl State {
+    fn normalize(&mut self) {
+        self.pairs.sort_unstable();
+    }
+
+    fn is_done(&self) -> bool {
+        self.pairs
+            .iter()
+            .all(|&(g, m)| g == FLOORS - 1 && m == FLOORS - 1)
+    }
+
+    /// Checks that no microchip is on a floor with another generator without its own one.
+    fn is_safe(&self) -> bool {
+        self.pairs
+            .iter()
+            .all(|&(g, m)| g == m || self.pairs.iter().all(|&(other, _)| other != m))
+    }
+
+    /// Every safe state that can be reached with one elevator ride.
+    fn moves(&self) -> Vec<State> {
+        // (pair index, is microchip)
+        let items: Vec<(usize, bool)> = self
+            .pairs
+            .iter()
+            .enumerate()
+            .flat_map(|(i, &(g, m))| {
+                let generator = (g == self.elevator).then_some((i, false));
+                let microchip = (m == self.elevator).then_some((i, true));
+                generator.into_iter().chain(microchip)
+            })
+            .collect();
+
+        let mut floors = vec![];
+        if self.elevator + 1 < FLOORS {
+            floors.push(self.elevator + 1);
+        }
+        // No reason to go down if everything below is already empty
+        if self.elevator > 0
+            && self
+                .pairs
+                .iter()
+                .any(|&(g, m)| g < self.elevator || m < self.elevator)
+        {
+            floors.push(self.elevator - 1);
+        }
+
+        let mut states = vec![];
+        for floor in floors {
+            for (i, first) in items.iter().enumerate() {
+                for second in std::iter::once(None).chain(items[i + 1..].iter().map(Some)) {
+                    let mut next = self.clone();
+                    next.elevator = floor;
+                    for &(pair, is_microchip) in std::iter::once(first).chain(second) {
+                        if is_microchip {
+                            next.pairs[pair].1 = floor;
+                        } else {
+                            next.pairs[pair].0 = floor;
+                        }
+                    }
+                    if next.is_safe() {
+                        next.normalize();
+                        states.push(next);
+                    }
+                }
+            }
+        }
+        states
+    }
+}
+
+fn load_input(name: &str) -> State {
+    let file = File::open(name).unwrap_or_else(|_| panic!("No \"{}\" file found", name));
+    let reader = BufReader::new(file);
+    // element -> (generator, microchip)
+    let mut elements: HashMap<String, (u8, u8)> = HashMap::new();
+    for (floor, line) in reader.lines_unwrap().enumerate() {
+        let words: Vec<&str> = line
+            .split_whitespace()
+            .map(|w| w.trim_end_matches([',', '.']))
+            .collect();
+        for pair in words.windows(2) {
+            match pair[1] {
+                "generator" => elements.entry(pair[0].to_string()).or_default().0 = floor as u8,
+                "microchip" => {
+                    let element = pair[0].trim_end_matches("-compatible");
+                    elements.entry(element.to_string()).or_default().1 = floor as u8
+                }
+                _ => {}
+            }
+        }
+    }
+    let mut state = State {
+        elevator: 0,
+        pairs: elements.into_values().collect(),
+    };
+    state.normalize();
+    state
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part_1_test() {
+        let input = load_input("example");
+        assert_eq!(
+            input,
+            State {
+                elevator: 0,
+                pairs: vec![(1, 0), (2, 0)]
+            }
+        );
+        assert_eq!(part_1(&input), 11);
+    }
+
+    #[test]
+    fn normalized() {
+        let mut first = State {
+            elevator: 1,
+            pairs: vec![(2, 1), (0, 3), (1, 1)],
+        };
+        let mut second = State {
+            elevator: 1,
+            pairs: vec![(1, 1), (2, 1), (0, 3)],
+        };
+        first.normalize();
+        second.normalize();
+        assert_eq!(first, second);
+        assert!(!first.is_safe());
+        assert!(State {
+            elevator: 0,
+            pairs: vec![(1, 1), (0, 2)],
+        }
+        .is_safe());
+    }
 }
@@ -1,3 +1,54 @@
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::BufReader;
+
+use utils::assembunny::{machine, Op};
+
 fn main() {
-    println!("Hello, world!");
+    let input = load_input("input");
+    println!("Solution for part 1: {}", run(&input, 0));
+    println!("Solution for part 2: {}", run(&input, 1));
+}
+
+/// Runs the program with `c` set to the value and returns `a`.
+fn run(program: &[Op], c: i64) -> i64 {
+    let mut machine = machine(program.to_vec());
+    machine.registers['c'] = c;
+    machine.run();
+    machine.registers['a']
+}
+
+fn load_input(name: &str) -> Vec<Op> {
+    let file = File::open(name).unwrap_or_else(|_| panic!("No \"{}\" file found", name));
+    let reader = BufReader::new(file);
+    reader
+        .lines()
+        .map(|l| l.unwrap().parse().unwrap())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(lines: &[&str]) -> Vec<Op> {
+        lines.iter().map(|l| l.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn part_1_test() {
+        let program = parse(&["cpy 41 a", "inc a", "inc a", "dec a", "jnz a 2", "dec a"]);
+        assert_eq!(run(&program, 0), 42);
+    }
+
+    #[test]
+    fn part_2_test() {
+        // Fibonacci numbers like the real input, c decides how many
+        let program = parse(&[
+            "cpy 1 a", "cpy 1 b", "cpy 10 d", "jnz c 2", "jnz 1 5", "cpy 5 c", "inc d", "dec c",
+            "jnz c -2", "cpy a c", "inc a", "dec b", "jnz b -2", "cpy c b", "dec d", "jnz d -6",
+        ]);
+        assert_eq!(run(&program, 0), 144);
+        assert_eq!(run(&program, 1), 1597);
+    }
 }
@@ -1,3 +1,107 @@
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::BufReader;
+
+use utils::cursor::{Cursor, Direction};
+use utils::{Grid, GridMaker};
+
+/// How far past the target the maze gets generated, the path might have to go around walls
+const MARGIN: usize = 50;
+
 fn main() {
-    println!("Hello, world!");
+    let input = load_input("input");
+    println!("Solution for part 1: {}", part_1(input, (31, 39)));
+    println!("Solution for part 2: {}", part_2(input, 50));
+}
+
+fn part_1(favorite: usize, target: (usize, usize)) -> usize {
+    let maze = make_maze(favorite, target.0 + MARGIN, target.1 + MARGIN);
+    distances(&maze)[target.1][target.0].expect("Target can't be reached")
+}
+
+/// How many locations can be reached in at most `steps` steps.
+fn part_2(favorite: usize, steps: usize) -> usize {
+    let maze = make_maze(favorite, steps + 2, steps + 2);
+    distances(&maze)
+        .iter()
+        .flatten()
+        .filter(|d| matches!(d, Some(d) if *d <= steps))
+        .count()
+}
+
+/// `true` is a wall.
+fn make_maze(favorite: usize, width: usize, height: usize) -> Grid<bool> {
+    let mut maze = GridMaker::new(None);
+    for y in 0..height {
+        maze.push((0..width).map(|x| {
+            let value = x * x + 3 * x + 2 * x * y + y + y * y + favorite;
+            value.count_ones() % 2 == 1
+        }));
+    }
+    maze.finish()
+}
+
+/// The least amount of steps needed to get from `(1, 1)` to every location.
+fn distances(maze: &Grid<bool>) -> Grid<Option<usize>> {
+    let mut distances = vec![vec![None; maze[0].len()]; maze.len()];
+    distances[1][1] = Some(0);
+    let mut q = VecDeque::from([(Cursor::new(1, 1, &Direction::CARDINAL), 0)]);
+    while let Some((cur, steps)) = q.pop_front() {
+        for next in cur.get_moves_iter(|&wall| !wall, maze) {
+            let distance = next.index_grid_mut(&mut distances);
+            if distance.is_none() {
+                *distance = Some(steps + 1);
+                q.push_back((next, steps + 1));
+            }
+        }
+    }
+    distances
+}
+
+fn load_input(name: &str) -> usize {
+    let file = File::open(name).unwrap_or_else(|_| panic!("No \"{}\" file found", name));
+    let reader = BufReader::new(file);
+    reader
+        .lines()
+        .next()
+        .unwrap()
+        .unwrap()
+        .trim()
+        .parse()
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maze_test() {
+        let expected = [
+            ".#.####.##",
+            "..#..#...#",
+            "#....##...",
+            "###.#.###.",
+            ".##..#..#.",
+            "..##....#.",
+            "#...##.###",
+        ];
+        let maze: Vec<String> = make_maze(10, 10, 7)
+            .iter()
+            .map(|row| row.iter().map(|&w| if w { '#' } else { '.' }).collect())
+            .collect();
+        assert_eq!(maze, expected);
+    }
+
+    #[test]
+    fn part_1_test() {
+        assert_eq!(part_1(10, (7, 4)), 11);
+    }
+
+    #[test]
+    fn part_2_test() {
+        assert_eq!(part_2(10, 0), 1);
+        assert_eq!(part_2(10, 2), 5);
+    }
 }
@@ -1,3 +1,94 @@
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::BufReader;
+
+use utils::hash::{nibble, Digest, NonceSearch};
+
+/// How many hashes after a triple can have the matching five
+const WINDOW: u64 = 1000;
+
 fn main() {
-    println!("Hello, world!");
+    let input = load_input("input");
+    println!("Solution for part 1: {}", part_1(&input));
+    println!("Solution for part 2: {}", part_2(&input));
+}
+
+fn part_1(salt: &str) -> u64 {
+    find_key(salt, 0, 64)
+}
+
+fn part_2(salt: &str) -> u64 {
+    find_key(salt, 2016, 64)
+}
+
+/// Returns the index that makes the nth key.
+///
+/// Every hash with five of the same character in a row also has three of them so only the hashes
+/// with a triple need to be looked at.
+fn find_key(salt: &str, rounds: usize, n: usize) -> u64 {
+    let mut search = NonceSearch::new(salt, |d| triple(d).is_some()).stretched(rounds);
+    let mut candidates: VecDeque<(u64, Digest)> = VecDeque::new();
+    let mut found = 0;
+    loop {
+        if candidates.is_empty() {
+            candidates.extend(search.next());
+        }
+        let (index, digest) = candidates.pop_front().unwrap();
+        // Makes sure every hash in the window is there
+        while candidates.back().is_none_or(|&(i, _)| i <= index + WINDOW) {
+            candidates.extend(search.next());
+        }
+        let c = triple(&digest).unwrap();
+        if candidates
+            .iter()
+            .take_while(|&&(i, _)| i <= index + WINDOW)
+            .any(|(_, d)| has_five(d, c))
+        {
+            found += 1;
+            if found == n {
+                return index;
+            }
+        }
+    }
+}
+
+/// The hex digit of the first triple in the hash.
+fn triple(digest: &Digest) -> Option<u8> {
+    (0..30)
+        .map(|i| nibble(digest, i))
+        .enumerate()
+        .find_map(|(i, c)| (nibble(digest, i + 1) == c && nibble(digest, i + 2) == c).then_some(c))
+}
+
+fn has_five(digest: &Digest, c: u8) -> bool {
+    (0..28).any(|i| (i..i + 5).all(|j| nibble(digest, j) == c))
+}
+
+fn load_input(name: &str) -> String {
+    let file = File::open(name).unwrap_or_else(|_| panic!("No \"{}\" file found", name));
+    let reader = BufReader::new(file);
+    reader.lines().next().unwrap().unwrap().trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use utils::hash::md5;
+
+    #[test]
+    fn part_1_test() {
+        assert_eq!(triple(&md5("abc18")), Some(8));
+        assert_eq!(triple(&md5("abc17")), None);
+        assert!(has_five(&md5("abc816"), 0xe));
+        assert_eq!(find_key("abc", 0, 1), 39);
+        assert_eq!(find_key("abc", 0, 2), 92);
+        assert_eq!(part_1("abc"), 22728);
+    }
+
+    #[test]
+    fn part_2_test() {
+        // The full example takes too long without optimisations
+        assert_eq!(find_key("abc", 2016, 1), 10);
+    }
 }
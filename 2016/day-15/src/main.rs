@@ -1,3 +1,101 @@
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::BufReader;
+use std::str::FromStr;
+
 fn main() {
-    println!("Hello, world!");
+    let input = load_input("input");
+    println!("Solution for part 1: {}", part_1(&input));
+    println!("Solution for part 2: {}", part_2(&input));
+}
+
+fn part_1(discs: &[Disc]) -> u64 {
+    first_time(discs)
+}
+
+fn part_2(discs: &[Disc]) -> u64 {
+    let mut discs = discs.to_vec();
+    discs.push(Disc {
+        positions: 11,
+        start: 0,
+    });
+    first_time(&discs)
+}
+
+/// Finds the first time the button can be pressed so the capsule falls through every disc.
+///
+/// The discs get added one by one. Once a time works for some discs it keeps working every
+/// time the product of their positions passes so only those times need to be checked for the
+/// next disc. This is the Chinese remainder theorem done with a sieve.
+fn first_time(discs: &[Disc]) -> u64 {
+    let (mut time, mut step) = (0, 1);
+    for (i, disc) in discs.iter().enumerate() {
+        // The capsule reaches the disc i + 1 seconds after the press
+        while !disc.is_open(time + i as u64 + 1) {
+            time += step;
+        }
+        step *= disc.positions;
+    }
+    time
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Disc {
+    positions: u64,
+    /// Position at time 0
+    start: u64,
+}
+
+impl Disc {
+    fn is_open(&self, time: u64) -> bool {
+        (self.start + time).is_multiple_of(self.positions)
+    }
+}
+
+impl FromStr for Disc {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let words: Vec<&str> = s.trim_end_matches('.').split_whitespace().collect();
+        match words.as_slice() {
+            [_, _, _, positions, _, _, _, _, _, _, _, start] => Ok(Self {
+                positions: positions.parse().map_err(|_| ())?,
+                start: start.parse().map_err(|_| ())?,
+            }),
+            _ => Err(()),
+        }
+    }
+}
+
+fn load_input(name: &str) -> Vec<Disc> {
+    let file = File::open(name).unwrap_or_else(|_| panic!("No \"{}\" file found", name));
+    let reader = BufReader::new(file);
+    reader
+        .lines()
+        .map(|l| l.unwrap().parse().unwrap())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part_1_test() {
+        let input = load_input("example");
+        assert_eq!(
+            input,
+            [
+                Disc {
+                    positions: 5,
+                    start: 4
+                },
+                Disc {
+                    positions: 2,
+                    start: 1
+                }
+            ]
+        );
+        assert_eq!(part_1(&input), 5);
+    }
 }
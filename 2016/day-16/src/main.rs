@@ -1,3 +1,66 @@
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::BufReader;
+
 fn main() {
-    println!("Hello, world!");
+    let input = load_input("input");
+    println!("Solution for part 1: {}", checksum(&fill(&input, 272)));
+    println!("Solution for part 2: {}", checksum(&fill(&input, 35651584)));
+}
+
+/// Makes the data longer with the modified dragon curve until it fills the disk.
+fn fill(initial: &[bool], length: usize) -> Vec<bool> {
+    let mut data = initial.to_vec();
+    data.reserve(length * 2);
+    while data.len() < length {
+        let copy: Vec<bool> = data.iter().rev().map(|b| !b).collect();
+        data.push(false);
+        data.extend(copy);
+    }
+    data.truncate(length);
+    data
+}
+
+fn checksum(data: &[bool]) -> String {
+    let mut checksum = data.to_vec();
+    while checksum.len().is_multiple_of(2) {
+        checksum = checksum.chunks(2).map(|pair| pair[0] == pair[1]).collect();
+    }
+    to_string(&checksum)
+}
+
+fn to_string(data: &[bool]) -> String {
+    data.iter().map(|&b| if b { '1' } else { '0' }).collect()
+}
+
+fn parse(s: &str) -> Vec<bool> {
+    s.trim().chars().map(|c| c == '1').collect()
+}
+
+fn load_input(name: &str) -> Vec<bool> {
+    let file = File::open(name).unwrap_or_else(|_| panic!("No \"{}\" file found", name));
+    let reader = BufReader::new(file);
+    parse(&reader.lines().next().unwrap().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fill_test() {
+        assert_eq!(to_string(&fill(&parse("1"), 3)), "100");
+        assert_eq!(to_string(&fill(&parse("0"), 3)), "001");
+        assert_eq!(to_string(&fill(&parse("11111"), 11)), "11111000000");
+        assert_eq!(
+            to_string(&fill(&parse("111100001010"), 25)),
+            "1111000010100101011110000"
+        );
+    }
+
+    #[test]
+    fn part_1_test() {
+        assert_eq!(checksum(&parse("110010110100")), "100");
+        assert_eq!(checksum(&fill(&parse("10000"), 20)), "01100");
+    }
 }
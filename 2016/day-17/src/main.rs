@@ -1,3 +1,94 @@
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::BufReader;
+
+use utils::cursor::{Cursor, Direction, MoveResult};
+use utils::hash::{md5, nibble};
+use utils::{Grid, GridMaker};
+
+/// The doors in the order of the hash, with the letter used in the path
+const DOORS: [(char, Direction); 4] = [
+    ('U', Direction::N),
+    ('D', Direction::S),
+    ('L', Direction::W),
+    ('R', Direction::E),
+];
+
 fn main() {
-    println!("Hello, world!");
+    let input = load_input("input");
+    let paths = find_paths(&input);
+    println!("Solution for part 1: {}", part_1(&paths));
+    println!("Solution for part 2: {}", part_2(&paths));
+}
+
+fn part_1(paths: &[String]) -> String {
+    paths.first().expect("No path to the vault").clone()
+}
+
+fn part_2(paths: &[String]) -> usize {
+    paths.last().expect("No path to the vault").len()
+}
+
+/// Every path that reaches the vault, from the shortest to the longest.
+///
+/// The doors only depend on the path taken so far so nothing can be skipped, but once the vault
+/// is reached the path ends.
+fn find_paths(passcode: &str) -> Vec<String> {
+    let rooms: Grid<()> = GridMaker::new_empty(None, (), 4, 4);
+    let vault = (3, 3);
+    let mut paths = vec![];
+    let mut q = VecDeque::from([(Cursor::new(0, 0, &Direction::CARDINAL), String::new())]);
+    while let Some((cur, path)) = q.pop_front() {
+        if cur.get_coords() == vault {
+            paths.push(path);
+            continue;
+        }
+        let hash = md5(format!("{passcode}{path}"));
+        for (i, &(letter, direction)) in DOORS.iter().enumerate() {
+            if nibble(&hash, i) < 0xB {
+                continue;
+            }
+            let mut next = cur.clone();
+            if next.move_in_direction(|_| true, &rooms, direction) == MoveResult::Ok {
+                let mut next_path = path.clone();
+                next_path.push(letter);
+                q.push_back((next, next_path));
+            }
+        }
+    }
+    paths
+}
+
+fn load_input(name: &str) -> String {
+    let file = File::open(name).unwrap_or_else(|_| panic!("No \"{}\" file found", name));
+    let reader = BufReader::new(file);
+    reader.lines().next().unwrap().unwrap().trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part_1_test() {
+        assert_eq!(part_1(&find_paths("ihgpwlah")), "DDRRRD");
+        assert_eq!(part_1(&find_paths("kglvqrro")), "DDUDRLRRUDRD");
+        assert_eq!(
+            part_1(&find_paths("ulqzkmiv")),
+            "DRURDRUDDLLDLUURRDULRLDUUDDDRR"
+        );
+    }
+
+    #[test]
+    fn part_2_test() {
+        assert_eq!(part_2(&find_paths("ihgpwlah")), 370);
+        assert_eq!(part_2(&find_paths("kglvqrro")), 492);
+        assert_eq!(part_2(&find_paths("ulqzkmiv")), 830);
+    }
+
+    #[test]
+    fn locked_in() {
+        assert!(find_paths("hijkl").is_empty());
+    }
 }
@@ -1,3 +1,59 @@
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::BufReader;
+
 fn main() {
-    println!("Hello, world!");
+    let input = load_input("input");
+    println!("Solution for part 1: {}", count_safe(&input, 40));
+    println!("Solution for part 2: {}", count_safe(&input, 400000));
+}
+
+/// Counts the safe tiles in the first `rows` rows.
+fn count_safe(first: &[bool], rows: usize) -> usize {
+    let mut row = first.to_vec();
+    let mut safe = 0;
+    for _ in 0..rows {
+        safe += row.iter().filter(|&&trap| !trap).count();
+        row = next_row(&row);
+    }
+    safe
+}
+
+/// A tile is a trap if exactly one of the tiles to its left and right above it is a trap, the
+/// walls count as safe.
+fn next_row(row: &[bool]) -> Vec<bool> {
+    (0..row.len())
+        .map(|i| {
+            let left = i > 0 && row[i - 1];
+            let right = row.get(i + 1).copied().unwrap_or(false);
+            left != right
+        })
+        .collect()
+}
+
+fn parse(s: &str) -> Vec<bool> {
+    s.trim().chars().map(|c| c == '^').collect()
+}
+
+fn load_input(name: &str) -> Vec<bool> {
+    let file = File::open(name).unwrap_or_else(|_| panic!("No \"{}\" file found", name));
+    let reader = BufReader::new(file);
+    parse(&reader.lines().next().unwrap().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn next_row_test() {
+        assert_eq!(next_row(&parse("..^^.")), parse(".^^^^"));
+        assert_eq!(next_row(&parse(".^^^^")), parse("^^..^"));
+    }
+
+    #[test]
+    fn part_1_test() {
+        assert_eq!(count_safe(&parse("..^^."), 3), 6);
+        assert_eq!(count_safe(&parse(".^^.^.^^^^"), 10), 38);
+    }
 }
@@ -1,3 +1,87 @@
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::BufReader;
+
 fn main() {
-    println!("Hello, world!");
+    let input = load_input("input");
+    println!("Solution for part 1: {}", part_1(input));
+    println!("Solution for part 2: {}", part_2(input));
+}
+
+/// Every elf steals from the one to their left, the Josephus problem with every second one
+/// removed.
+///
+/// After a full power of 2 elves the first elf wins, every elf more than that moves the winner 2
+/// places further.
+fn part_1(elves: usize) -> usize {
+    let power = 1 << elves.ilog2();
+    2 * (elves - power) + 1
+}
+
+/// Every elf steals from the one across the circle.
+///
+/// After a full power of 3 elves the last elf wins. For the next elves up to that power again the
+/// winner moves 1 place per elf and after that 2 places.
+fn part_2(elves: usize) -> usize {
+    let power = 3_usize.pow(elves.ilog(3));
+    if elves == power {
+        elves
+    } else if elves <= 2 * power {
+        elves - power
+    } else {
+        2 * elves - 3 * power
+    }
+}
+
+fn load_input(name: &str) -> usize {
+    let file = File::open(name).unwrap_or_else(|_| panic!("No \"{}\" file found", name));
+    let reader = BufReader::new(file);
+    reader
+        .lines()
+        .next()
+        .unwrap()
+        .unwrap()
+        .trim()
+        .parse()
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Plays the game one steal at a time, `victim` says how far to the left of the current elf the
+    /// victim sits for the size of the circle
+    fn simulate<F>(elves: usize, victim: F) -> usize
+    where
+        F: Fn(usize) -> usize,
+    {
+        let mut circle: Vec<usize> = (1..=elves).collect();
+        let mut current = 0;
+        while circle.len() > 1 {
+            let removed = (current + victim(circle.len())) % circle.len();
+            circle.remove(removed);
+            if removed > current {
+                current += 1;
+            }
+            current %= circle.len();
+        }
+        circle[0]
+    }
+
+    #[test]
+    fn part_1_test() {
+        assert_eq!(part_1(5), 3);
+        for elves in 1..200 {
+            assert_eq!(part_1(elves), simulate(elves, |_| 1), "{elves}");
+        }
+    }
+
+    #[test]
+    fn part_2_test() {
+        assert_eq!(part_2(5), 2);
+        for elves in 1..200 {
+            assert_eq!(part_2(elves), simulate(elves, |len| len / 2), "{elves}");
+        }
+    }
 }
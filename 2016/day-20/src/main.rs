@@ -1,3 +1,76 @@
+use std::fs::File;
+use std::io::BufReader;
+
+use utils::BufReadExt;
+
 fn main() {
-    println!("Hello, world!");
+    let input = load_input("input");
+    let blocked = merge(input);
+    println!("Solution for part 1: {}", part_1(&blocked));
+    println!("Solution for part 2: {}", part_2(&blocked, u32::MAX as u64));
+}
+
+fn part_1(blocked: &[(u64, u64)]) -> u64 {
+    let mut lowest = 0;
+    for &(start, end) in blocked {
+        if start > lowest {
+            break;
+        }
+        lowest = end + 1;
+    }
+    lowest
+}
+
+/// Counts the allowed IPs from 0 to `max`.
+fn part_2(blocked: &[(u64, u64)], max: u64) -> u64 {
+    let blocked_count: u64 = blocked
+        .iter()
+        .filter(|&&(start, _)| start <= max)
+        .map(|&(start, end)| end.min(max) - start + 1)
+        .sum();
+    max + 1 - blocked_count
+}
+
+/// Sorts the ranges and merges the ones that overlap or touch.
+fn merge(mut ranges: Vec<(u64, u64)>) -> Vec<(u64, u64)> {
+    ranges.sort_unstable();
+    let mut merged: Vec<(u64, u64)> = vec![];
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 + 1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+fn load_input(name: &str) -> Vec<(u64, u64)> {
+    let file = File::open(name).unwrap_or_else(|_| panic!("No \"{}\" file found", name));
+    let reader = BufReader::new(file);
+    reader
+        .lines_unwrap()
+        .map(|l| {
+            let (start, end) = l.split_once('-').unwrap();
+            (start.parse().unwrap(), end.parse().unwrap())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part_1_test() {
+        let blocked = merge(load_input("example"));
+        assert_eq!(blocked, [(0, 2), (4, 8)]);
+        assert_eq!(part_1(&blocked), 3);
+        assert_eq!(part_1(&merge(vec![(0, 3), (4, 5)])), 6);
+    }
+
+    #[test]
+    fn part_2_test() {
+        let blocked = merge(load_input("example"));
+        assert_eq!(part_2(&blocked, 9), 2);
+    }
 }
@@ -1,3 +1,187 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::str::FromStr;
+
+use utils::BufReadExt;
+
 fn main() {
-    println!("Hello, world!");
+    let input = load_input("input");
+    println!("Solution for part 1: {}", scramble("abcdefgh", &input));
+    println!("Solution for part 2: {}", unscramble("fbgdceah", &input));
+}
+
+fn scramble(password: &str, operations: &[Scramble]) -> String {
+    let mut password: Vec<char> = password.chars().collect();
+    for op in operations {
+        op.apply(&mut password);
+    }
+    password.into_iter().collect()
+}
+
+/// Undoes the operations by applying their inverses in reverse order.
+fn unscramble(scrambled: &str, operations: &[Scramble]) -> String {
+    let inverted: Vec<Scramble> = operations.iter().rev().map(|op| op.inverse()).collect();
+    scramble(scrambled, &inverted)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Scramble {
+    SwapPosition(usize, usize),
+    SwapLetter(char, char),
+    RotateLeft(usize),
+    RotateRight(usize),
+    /// Rotates right once, then by the index of the letter and once more if the index was at
+    /// least 4
+    RotateBased(char),
+    /// Undoes [`Scramble::RotateBased`], isn't part of the puzzle input
+    UnrotateBased(char),
+    Reverse(usize, usize),
+    Move(usize, usize),
+}
+
+impl Scramble {
+    fn apply(&self, password: &mut [char]) {
+        match *self {
+            Scramble::SwapPosition(x, y) => password.swap(x, y),
+            Scramble::SwapLetter(a, b) => {
+                for c in password.iter_mut() {
+                    if *c == a {
+                        *c = b;
+                    } else if *c == b {
+                        *c = a;
+                    }
+                }
+            }
+            Scramble::RotateLeft(n) => password.rotate_left(n % password.len()),
+            Scramble::RotateRight(n) => password.rotate_right(n % password.len()),
+            Scramble::RotateBased(letter) => {
+                let index = position(password, letter);
+                password.rotate_right(based_rotation(index) % password.len());
+            }
+            Scramble::UnrotateBased(letter) => {
+                let len = password.len();
+                let current = position(password, letter);
+                // The index the letter had before the rotation, always unique for 8 letters
+                let original = (0..len)
+                    .find(|&i| (i + based_rotation(i)) % len == current)
+                    .expect("Rotation can't be undone");
+                password.rotate_left(based_rotation(original) % len);
+            }
+            Scramble::Reverse(x, y) => password[x..=y].reverse(),
+            Scramble::Move(x, y) => {
+                if x < y {
+                    password[x..=y].rotate_left(1);
+                } else {
+                    password[y..=x].rotate_right(1);
+                }
+            }
+        }
+    }
+
+    /// The operation that undoes this one.
+    fn inverse(&self) -> Self {
+        match *self {
+            Scramble::SwapPosition(_, _) | Scramble::SwapLetter(_, _) | Scramble::Reverse(_, _) => {
+                *self
+            }
+            Scramble::RotateLeft(n) => Scramble::RotateRight(n),
+            Scramble::RotateRight(n) => Scramble::RotateLeft(n),
+            Scramble::RotateBased(c) => Scramble::UnrotateBased(c),
+            Scramble::UnrotateBased(c) => Scramble::RotateBased(c),
+            Scramble::Move(x, y) => Scramble::Move(y, x),
+        }
+    }
+}
+
+fn position(password: &[char], letter: char) -> usize {
+    password
+        .iter()
+        .position(|&c| c == letter)
+        .unwrap_or_else(|| panic!("No letter {letter} in the password"))
+}
+
+/// How far [`Scramble::RotateBased`] rotates when the letter is at `index`.
+fn based_rotation(index: usize) -> usize {
+    1 + index + usize::from(index >= 4)
+}
+
+impl FromStr for Scramble {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let words: Vec<&str> = s.split_whitespace().collect();
+        let number =
+            |i: usize| -> Result<usize, ()> { words.get(i).ok_or(())?.parse().map_err(|_| ()) };
+        let letter = |i: usize| -> Result<char, ()> {
+            let mut chars = words.get(i).ok_or(())?.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Ok(c),
+                _ => Err(()),
+            }
+        };
+        Ok(match (words.first(), words.get(1)) {
+            (Some(&"swap"), Some(&"position")) => Scramble::SwapPosition(number(2)?, number(5)?),
+            (Some(&"swap"), Some(&"letter")) => Scramble::SwapLetter(letter(2)?, letter(5)?),
+            (Some(&"rotate"), Some(&"left")) => Scramble::RotateLeft(number(2)?),
+            (Some(&"rotate"), Some(&"right")) => Scramble::RotateRight(number(2)?),
+            (Some(&"rotate"), Some(&"based")) => Scramble::RotateBased(letter(6)?),
+            (Some(&"reverse"), _) => Scramble::Reverse(number(2)?, number(4)?),
+            (Some(&"move"), _) => Scramble::Move(number(2)?, number(5)?),
+            _ => return Err(()),
+        })
+    }
+}
+
+fn load_input(name: &str) -> Vec<Scramble> {
+    let file = File::open(name).unwrap_or_else(|_| panic!("No \"{}\" file found", name));
+    let reader = BufReader::new(file);
+    reader.lines_unwrap().map(|l| l.parse().unwrap()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part_1_test() {
+        let input = load_input("example");
+        assert_eq!(input[0], Scramble::SwapPosition(4, 0));
+        assert_eq!(input[6], Scramble::RotateBased('b'));
+        let steps = [
+            "ebcda", "edcba", "abcde", "bcdea", "bdeac", "abdec", "ecabd", "decab",
+        ];
+        for (i, expected) in steps.iter().enumerate() {
+            assert_eq!(scramble("abcde", &input[..=i]), *expected);
+        }
+    }
+
+    #[test]
+    fn part_2_test() {
+        let input = load_input("example");
+        for password in ["abcdefgh", "hgfedcba", "fbgdceah", "dcbaefgh"] {
+            assert_eq!(unscramble(&scramble(password, &input), &input), password);
+        }
+    }
+
+    #[test]
+    fn inverse() {
+        let password: Vec<char> = "abcdefgh".chars().collect();
+        for op in [
+            Scramble::SwapPosition(1, 6),
+            Scramble::SwapLetter('h', 'c'),
+            Scramble::RotateLeft(11),
+            Scramble::RotateRight(3),
+            Scramble::Reverse(2, 7),
+            Scramble::Move(6, 1),
+        ]
+        .into_iter()
+        .chain("abcdefgh".chars().map(Scramble::RotateBased))
+        {
+            let mut changed = password.clone();
+            op.apply(&mut changed);
+            op.inverse().apply(&mut changed);
+            assert_eq!(changed, password, "{op:?}");
+            assert_eq!(op.inverse().inverse(), op);
+        }
+    }
 }
@@ -1,3 +1,137 @@
+use std::collections::{HashSet, VecDeque};
+use std::fs::File;
+use std::io::BufReader;
+use std::str::FromStr;
+
+use utils::cursor::{Cursor, Direction};
+use utils::{BufReadExt, Grid, GridMaker};
+
 fn main() {
-    println!("Hello, world!");
+    let input = load_input("input");
+    println!("Solution for part 1: {}", part_1(&input));
+    println!("Solution for part 2: {}", part_2(&input));
+}
+
+fn part_1(nodes: &Grid<Node>) -> usize {
+    let nodes: Vec<&Node> = nodes.iter().flatten().collect();
+    let mut count = 0;
+    for (i, a) in nodes.iter().enumerate() {
+        if a.used == 0 {
+            continue;
+        }
+        count += nodes
+            .iter()
+            .enumerate()
+            .filter(|&(j, b)| i != j && a.used <= b.avail())
+            .count();
+    }
+    count
+}
+
+/// Moves the empty node around like in a sliding puzzle until the goal data is at `(0, 0)`.
+///
+/// The nodes are either small enough that all of them can take the data of any other node or
+/// so full that their data can't be moved anywhere, so the only thing that ever moves is the
+/// empty node.
+fn part_2(nodes: &Grid<Node>) -> usize {
+    let empty = nodes
+        .iter()
+        .enumerate()
+        .find_map(|(y, row)| row.iter().position(|n| n.used == 0).map(|x| (x, y)))
+        .expect("No empty node");
+    let empty_size = nodes[empty.1][empty.0].size;
+    let goal = (nodes[0].len() - 1, 0);
+
+    let start = Cursor::new(empty.0, empty.1, &Direction::CARDINAL);
+    let mut seen = HashSet::from([(empty, goal)]);
+    let mut q = VecDeque::from([(start, goal, 0)]);
+    while let Some((cur, goal, steps)) = q.pop_front() {
+        if goal == (0, 0) {
+            return steps;
+        }
+        for next in cur.get_moves_iter(|n: &Node| n.used <= empty_size, nodes) {
+            // Moving the goal data into the empty node swaps their places
+            let next_goal = if next.get_coords() == goal {
+                cur.get_coords()
+            } else {
+                goal
+            };
+            if seen.insert((next.get_coords(), next_goal)) {
+                q.push_back((next, next_goal, steps + 1));
+            }
+        }
+    }
+    panic!("The goal data can't be moved")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Node {
+    size: usize,
+    used: usize,
+}
+
+impl Node {
+    fn avail(&self) -> usize {
+        self.size - self.used
+    }
+}
+
+impl FromStr for Node {
+    type Err = ();
+
+    /// Parses the size and used columns, the rest gets calculated from them.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut columns = s
+            .split_whitespace()
+            .skip(1)
+            .map(|c| c.trim_end_matches('T').parse::<usize>());
+        match (columns.next(), columns.next()) {
+            (Some(Ok(size)), Some(Ok(used))) => Ok(Self { size, used }),
+            _ => Err(()),
+        }
+    }
+}
+
+fn load_input(name: &str) -> Grid<Node> {
+    let file = File::open(name).unwrap_or_else(|_| panic!("No \"{}\" file found", name));
+    let reader = BufReader::new(file);
+    let mut nodes: Vec<((usize, usize), Node)> = vec![];
+    for line in reader.lines_unwrap().skip(2) {
+        let name = line.split_whitespace().next().unwrap();
+        let (x, y) = name
+            .strip_prefix("/dev/grid/node-x")
+            .unwrap()
+            .split_once("-y")
+            .unwrap();
+        let position = (x.parse().unwrap(), y.parse().unwrap());
+        nodes.push((position, line.parse().unwrap()));
+    }
+    // The nodes are listed column by column
+    nodes.sort_unstable_by_key(|&((x, y), _)| (y, x));
+    let width = nodes.iter().map(|&((x, _), _)| x + 1).max().unwrap();
+
+    let mut grid = GridMaker::new(None);
+    for row in nodes.chunks(width) {
+        grid.push(row.iter().map(|&(_, node)| node));
+    }
+    grid.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part_1_test() {
+        let input = load_input("example");
+        assert_eq!(input.len(), 3);
+        assert_eq!(input[1][2], Node { size: 9, used: 8 });
+        assert_eq!(part_1(&input), 7);
+    }
+
+    #[test]
+    fn part_2_test() {
+        let input = load_input("example");
+        assert_eq!(part_2(&input), 7);
+    }
 }
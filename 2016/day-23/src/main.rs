@@ -1,3 +1,81 @@
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::BufReader;
+
+use utils::assembunny::{machine, Op};
+
 fn main() {
-    println!("Hello, world!");
+    let input = load_input("input");
+    println!("Solution for part 1: {}", run(&input, 7));
+    // Only feasible because the multiplication loops get done at once
+    println!("Solution for part 2: {}", run(&input, 12));
+}
+
+/// Runs the program with `a` set to the amount of eggs and returns `a`.
+fn run(program: &[Op], eggs: i64) -> i64 {
+    let mut machine = machine(program.to_vec());
+    machine.registers['a'] = eggs;
+    machine.run();
+    machine.registers['a']
+}
+
+fn load_input(name: &str) -> Vec<Op> {
+    let file = File::open(name).unwrap_or_else(|_| panic!("No \"{}\" file found", name));
+    let reader = BufReader::new(file);
+    reader
+        .lines()
+        .map(|l| l.unwrap().parse().unwrap())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(lines: &[&str]) -> Vec<Op> {
+        lines.iter().map(|l| l.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn part_1_test() {
+        let program = parse(&[
+            "cpy 2 a", "tgl a", "tgl a", "tgl a", "cpy 1 a", "dec a", "dec a",
+        ]);
+        assert_eq!(run(&program, 0), 3);
+    }
+
+    /// The real inputs calculate `a! + x * y`, toggling their own jumps to end the loop.
+    #[test]
+    fn factorial() {
+        let program = parse(&[
+            "cpy a b",
+            "dec b",
+            "cpy a d",
+            "cpy 0 a",
+            "cpy b c",
+            "inc a",
+            "dec c",
+            "jnz c -2",
+            "dec d",
+            "jnz d -5",
+            "dec b",
+            "cpy b c",
+            "cpy c d",
+            "dec d",
+            "inc c",
+            "jnz d -2",
+            "tgl c",
+            "cpy -16 c",
+            "jnz 1 c",
+            "cpy 73 c",
+            "jnz 71 d",
+            "inc a",
+            "inc d",
+            "jnz d -2",
+            "inc c",
+            "jnz c -5",
+        ]);
+        assert_eq!(run(&program, 7), 5040 + 73 * 71);
+        assert_eq!(run(&program, 12), 479001600 + 73 * 71);
+    }
 }
@@ -1,3 +1,101 @@
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::BufReader;
+
+use utils::cursor::{Cursor, Direction};
+use utils::tsp::{held_karp, Goal, Route};
+use utils::{BufReadExt, Grid, GridMaker};
+
 fn main() {
-    println!("Hello, world!");
+    let input = load_input("input");
+    let distances = distances(&input);
+    println!("Solution for part 1: {}", part_1(&distances));
+    println!("Solution for part 2: {}", part_2(&distances));
+}
+
+fn part_1(distances: &[Vec<usize>]) -> usize {
+    held_karp(distances, Goal::Min, Route::Path, Some(0))
+        .unwrap()
+        .0
+}
+
+/// Same as part 1 but the robot has to go back to 0 at the end.
+fn part_2(distances: &[Vec<usize>]) -> usize {
+    held_karp(distances, Goal::Min, Route::Tour, Some(0))
+        .unwrap()
+        .0
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tile {
+    Wall,
+    Open,
+    /// A location the robot has to visit
+    Point(usize),
+}
+
+/// `distances[a][b]` is the least amount of steps from point `a` to point `b`.
+fn distances(grid: &Grid<Tile>) -> Vec<Vec<usize>> {
+    let mut points = vec![];
+    for (y, row) in grid.iter().enumerate() {
+        for (x, tile) in row.iter().enumerate() {
+            if let Tile::Point(n) = tile {
+                points.push((*n, x, y));
+            }
+        }
+    }
+    points.sort_unstable();
+
+    let mut distances = vec![vec![usize::MAX; points.len()]; points.len()];
+    for &(from, x, y) in &points {
+        let mut seen = vec![vec![false; grid[0].len()]; grid.len()];
+        seen[y][x] = true;
+        let mut q = VecDeque::from([(Cursor::new(x, y, &Direction::CARDINAL), 0)]);
+        while let Some((cur, steps)) = q.pop_front() {
+            if let Tile::Point(to) = cur.index_grid(grid) {
+                distances[from][*to] = steps;
+            }
+            for next in cur.get_moves_iter(|&t| t != Tile::Wall, grid) {
+                let seen = next.index_grid_mut(&mut seen);
+                if !*seen {
+                    *seen = true;
+                    q.push_back((next, steps + 1));
+                }
+            }
+        }
+    }
+    distances
+}
+
+fn load_input(name: &str) -> Grid<Tile> {
+    let file = File::open(name).unwrap_or_else(|_| panic!("No \"{}\" file found", name));
+    let reader = BufReader::new(file);
+    let mut grid = GridMaker::new(None);
+    for line in reader.lines_unwrap() {
+        grid.push(line.chars().map(|c| match c {
+            '#' => Tile::Wall,
+            '.' => Tile::Open,
+            _ => Tile::Point(c.to_digit(10).unwrap() as usize),
+        }));
+    }
+    grid.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part_1_test() {
+        let input = load_input("example");
+        let distances = distances(&input);
+        assert_eq!(distances[0], [0, 2, 8, 10, 2]);
+        assert_eq!(part_1(&distances), 14);
+    }
+
+    #[test]
+    fn part_2_test() {
+        let input = load_input("example");
+        assert_eq!(part_2(&distances(&input)), 20);
+    }
 }
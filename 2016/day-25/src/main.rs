@@ -1,3 +1,107 @@
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::BufReader;
+
+use utils::assembunny::{machine, Op};
+use utils::vm::Stop;
+
+/// Gives up on a value of `a` after this many instructions without a repeated state
+const MAX_STEPS: usize = 1_000_000;
+
 fn main() {
-    println!("Hello, world!");
+    let input = load_input("input");
+    println!("Solution for part 1: {}", part_1(&input));
+}
+
+fn part_1(program: &[Op]) -> i64 {
+    (1..).find(|&a| is_clock(program, a)).unwrap()
+}
+
+/// Checks if the program outputs `0, 1, 0, 1, ...` forever when `a` starts with the given value.
+///
+/// The output only depends on the state of the machine so once it is in the same state at an `out`
+/// as before it will keep repeating the same output.
+fn is_clock(program: &[Op], a: i64) -> bool {
+    let mut machine = machine(program.to_vec());
+    machine.registers['a'] = a;
+    let mut seen = HashSet::new();
+    loop {
+        let len = machine.output.len();
+        let stop = machine.run_until(|m| m.output.len() > len || m.steps() > MAX_STEPS);
+        if stop != Stop::Condition || machine.output.len() == len {
+            return false;
+        }
+        if machine.output[len] != len as i64 % 2 {
+            return false;
+        }
+        if !seen.insert((machine.pc(), machine.registers.clone(), len % 2)) {
+            return true;
+        }
+    }
+}
+
+fn load_input(name: &str) -> Vec<Op> {
+    let file = File::open(name).unwrap_or_else(|_| panic!("No \"{}\" file found", name));
+    let reader = BufReader::new(file);
+    reader
+        .lines()
+        .map(|l| l.unwrap().parse().unwrap())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Outputs the bits of `a + 12` forever, lowest first
+    const PROGRAM: &str = "cpy a d
+cpy 4 c
+cpy 3 b
+inc d
+dec b
+jnz b -2
+dec c
+jnz c -5
+cpy d a
+jnz 0 0
+cpy a b
+cpy 0 a
+cpy 2 c
+jnz b 2
+jnz 1 6
+dec b
+dec c
+jnz c -4
+inc a
+jnz 1 -7
+cpy 2 b
+jnz c 2
+jnz 1 4
+dec b
+dec c
+jnz 1 -4
+jnz 0 0
+out b
+jnz a -19
+jnz 1 -21";
+
+    fn parse(program: &str) -> Vec<Op> {
+        program.lines().map(|l| l.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn part_1_test() {
+        let program = parse(PROGRAM);
+        // 30 + 12 = 0b101010
+        assert_eq!(part_1(&program), 30);
+        assert!(is_clock(&program, 0b1010_1010 - 12));
+        assert!(!is_clock(&program, 31));
+    }
+
+    #[test]
+    fn no_output() {
+        assert!(!is_clock(&parse("inc a\njnz 1 -1"), 0));
+        assert!(!is_clock(&parse("out 0\nout 1\nout 1"), 0));
+    }
 }
@@ -14,7 +14,7 @@ fn main() {
             first_run = false;
         }
     }
-    print_output(&points);
+    println!("Solution for part 2: {}", part_2(&points));
 }
 
 fn fold_paper(instruction: &Split, points: &HashSet<(usize, usize)>) -> HashSet<(usize, usize)> {
@@ -48,29 +48,8 @@ fn fold_paper(instruction: &Split, points: &HashSet<(usize, usize)>) -> HashSet<
     new_points
 }
 
-fn print_output(points: &HashSet<(usize, usize)>) {
-    let mut max_x: usize = 0;
-    let mut max_y: usize = 0;
-    println!("Solution for part 2:\n");
-    for point in points {
-        if point.0 > max_x {
-            max_x = point.0;
-        }
-        if point.1 > max_y {
-            max_y = point.1;
-        }
-    }
-    for y in 0..=max_y {
-        let mut line = String::new();
-        for x in 0..=max_x {
-            if points.contains(&(x, y)) {
-                line.push('X');
-            } else {
-                line.push(' ')
-            }
-        }
-        println!("{}", line);
-    }
+fn part_2(points: &HashSet<(usize, usize)>) -> String {
+    utils::ocr::recognise_points(points.iter().copied())
 }
 
 fn load_input() -> (HashSet<(usize, usize)>, Vec<Split>) {
@@ -116,3 +95,24 @@ impl FromStr for Split {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part_2_test() {
+        // An "L" and its mirror image below the fold line
+        let mut points: HashSet<(usize, usize)> = HashSet::new();
+        for y in 0..6 {
+            points.insert((0, y));
+            points.insert((0, 14 - y));
+        }
+        for x in 1..4 {
+            points.insert((x, 5));
+            points.insert((x, 9));
+        }
+        let folded = fold_paper(&Split::Horizontal(7), &points);
+        assert_eq!(part_2(&folded), "L");
+    }
+}
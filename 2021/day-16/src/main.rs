@@ -1,8 +1,11 @@
+use std::fmt::Display;
 use std::fs::File;
 use std::io::prelude::*;
 use std::io::BufReader;
 use std::str::FromStr;
 
+use utils::bits::{from_hex, to_hex, BitReader, BitWriter};
+
 fn main() {
     let input = load_input();
     println!("Solution for part 1: {}", input.count_versions());
@@ -13,50 +16,21 @@ fn load_input() -> Packet {
     let file = File::open("input").expect("No input file found");
     let reader = BufReader::new(file);
     let line = reader.lines().next().unwrap().unwrap();
-    let binary = convert_hex_to_binary(&line);
-    Packet::from_str(&binary).unwrap()
-}
-
-fn convert_hex_to_binary(s: &str) -> String {
-    let mut binary = String::new();
-    for c in s.chars() {
-        binary.push_str(match c {
-            '0' => "0000",
-            '1' => "0001",
-            '2' => "0010",
-            '3' => "0011",
-            '4' => "0100",
-            '5' => "0101",
-            '6' => "0110",
-            '7' => "0111",
-            '8' => "1000",
-            '9' => "1001",
-            'A' => "1010",
-            'B' => "1011",
-            'C' => "1100",
-            'D' => "1101",
-            'E' => "1110",
-            'F' => "1111",
-            _ => unreachable!(),
-        })
-    }
-    binary
+    Packet::from_str(&line).unwrap()
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 struct Packet {
-    version: usize,
-    id: usize,
+    version: u8,
     packet_type: PacketType,
-    packet_length: usize,
 }
 
 impl Packet {
-    fn count_versions(&self) -> usize {
+    fn count_versions(&self) -> u64 {
         match &self.packet_type {
-            PacketType::Literal(_) => self.version,
-            PacketType::Operator(sub_packets) => {
-                let mut version_counter = self.version;
+            PacketType::Literal(_) => self.version as u64,
+            PacketType::Operator(_, _, sub_packets) => {
+                let mut version_counter = self.version as u64;
                 for sub_packet in sub_packets {
                     version_counter += sub_packet.count_versions();
                 }
@@ -65,167 +39,305 @@ impl Packet {
         }
     }
 
-    fn calculate_value(&self) -> usize {
+    fn calculate_value(&self) -> u64 {
         match &self.packet_type {
             PacketType::Literal(value) => *value,
-            PacketType::Operator(sub_packets) => match self.id {
-                0 => {
-                    let mut counter = 0;
-                    for sub_packet in sub_packets {
-                        counter += sub_packet.calculate_value();
-                    }
-                    counter
+            PacketType::Operator(op, _, sub_packets) => {
+                let mut values = sub_packets.iter().map(|p| p.calculate_value());
+                match op {
+                    Operator::Sum => values.sum(),
+                    Operator::Product => values.product(),
+                    Operator::Minimum => values.min().unwrap(),
+                    Operator::Maximum => values.max().unwrap(),
+                    Operator::GreaterThan => (values.next() > values.next()) as u64,
+                    Operator::LessThan => (values.next() < values.next()) as u64,
+                    Operator::EqualTo => (values.next() == values.next()) as u64,
                 }
-                1 => {
-                    if sub_packets.len() == 1 {
-                        sub_packets[0].calculate_value()
-                    } else {
-                        let mut counter = 1;
-                        for sub_packet in sub_packets {
-                            counter *= sub_packet.calculate_value();
-                        }
-                        counter
+            }
+        }
+    }
+
+    /// Reads a single packet, the reader ends up right after it.
+    fn read(reader: &mut BitReader) -> Self {
+        let version = reader.read_bits(3) as u8;
+        let id = reader.read_bits(3) as u8;
+        let packet_type = match Operator::from_id(id) {
+            None => {
+                let mut value = 0;
+                loop {
+                    let last = !reader.read_bit();
+                    value = value << 4 | reader.read_bits(4);
+                    if last {
+                        break;
                     }
                 }
-                2 => sub_packets
-                    .iter()
-                    .map(|x| x.calculate_value())
-                    .min()
-                    .unwrap(),
-                3 => sub_packets
-                    .iter()
-                    .map(|x| x.calculate_value())
-                    .max()
-                    .unwrap(),
-                5 => {
-                    let value_first = sub_packets[0].calculate_value();
-                    let value_second = sub_packets[1].calculate_value();
-                    if value_first > value_second {
-                        1
-                    } else {
-                        0
+                PacketType::Literal(value)
+            }
+            Some(op) => {
+                let mut sub_packets = Vec::new();
+                let length_type = if reader.read_bit() {
+                    let count = reader.read_bits(11);
+                    for _ in 0..count {
+                        sub_packets.push(Packet::read(reader));
                     }
-                }
-                6 => {
-                    let value_first = sub_packets[0].calculate_value();
-                    let value_second = sub_packets[1].calculate_value();
-                    if value_first < value_second {
-                        1
-                    } else {
-                        0
+                    LengthType::Count
+                } else {
+                    let length = reader.read_bits(15) as usize;
+                    let end = reader.position() + length;
+                    while reader.position() < end {
+                        sub_packets.push(Packet::read(reader));
                     }
+                    LengthType::Bits
+                };
+                PacketType::Operator(op, length_type, sub_packets)
+            }
+        };
+        Self {
+            version,
+            packet_type,
+        }
+    }
+
+    /// Writes the packet in the same format that [`Packet::read`] reads.
+    fn write(&self, writer: &mut BitWriter) {
+        writer.write_bits(self.version as u64, 3);
+        match &self.packet_type {
+            PacketType::Literal(value) => {
+                writer.write_bits(4, 3);
+                // Always at least 1 group even for 0
+                let groups = (64 - value.leading_zeros()).div_ceil(4).max(1);
+                for group in (0..groups).rev() {
+                    writer.write_bit(group != 0);
+                    writer.write_bits(value >> (group * 4), 4);
+                }
+            }
+            PacketType::Operator(op, length_type, sub_packets) => {
+                writer.write_bits(op.id() as u64, 3);
+                let mut sub_writer = BitWriter::new();
+                for sub_packet in sub_packets {
+                    sub_packet.write(&mut sub_writer);
                 }
-                7 => {
-                    let value_first = sub_packets[0].calculate_value();
-                    let value_second = sub_packets[1].calculate_value();
-                    if value_first == value_second {
-                        1
-                    } else {
-                        0
+                match length_type {
+                    LengthType::Bits => {
+                        writer.write_bit(false);
+                        writer.write_bits(sub_writer.len() as u64, 15);
+                    }
+                    LengthType::Count => {
+                        writer.write_bit(true);
+                        writer.write_bits(sub_packets.len() as u64, 11);
                     }
                 }
-                _ => unreachable!(),
-            },
+                writer.append(&sub_writer);
+            }
         }
     }
+
+    /// Encodes the packet as hex, the last digits get padded with 0s.
+    #[allow(dead_code)]
+    fn encode(&self) -> String {
+        let mut writer = BitWriter::new();
+        self.write(&mut writer);
+        to_hex(writer.as_bytes())
+    }
 }
 
 impl FromStr for Packet {
     type Err = ();
 
+    /// Parses the hex transmission.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let version = usize::from_str_radix(&s[0..3], 2).unwrap();
-        let id = usize::from_str_radix(&s[3..6], 2).unwrap();
-        let (packet, length) = PacketType::new(&id, &s[6..]);
-        Ok(Self {
-            version,
-            id,
-            packet_type: packet,
-            packet_length: length,
-        })
+        let bytes = from_hex(s).ok_or(())?;
+        Ok(Self::read(&mut BitReader::new(&bytes)))
     }
 }
 
-#[derive(Debug)]
-enum PacketType {
-    Literal(usize),
-    Operator(Vec<Packet>),
-}
-
-impl PacketType {
-    /// Creates a new PacketType and returns it's own packet length
-    ///
-    /// The length is returned because other Operator variants need to know the length of it's sub packets.
-    fn new(id: &usize, data: &str) -> (Self, usize) {
-        match *id {
-            4 => {
-                let mut binary = String::new();
-                let mut iter = data.chars();
-                let mut iter_counter = 0;
-                loop {
-                    iter_counter += 1;
-                    let mut last = false;
-                    if iter.next().unwrap() == '0' {
-                        last = true;
-                    }
-                    for _ in 0..4 {
-                        binary.push(iter.next().unwrap())
-                    }
-                    if last {
-                        break;
-                    }
-                }
-                (
-                    Self::Literal(usize::from_str_radix(&binary, 2).unwrap()),
-                    6 + (iter_counter * 5),
-                )
-            }
-            _ => {
-                let mut packet_length_counter = 6;
-                let (length_type, length) = match data.chars().next().unwrap() {
-                    '0' => (
-                        LengthType::Zero,
-                        usize::from_str_radix(&data[1..16], 2).unwrap(),
-                    ),
-                    '1' => (
-                        LengthType::One,
-                        usize::from_str_radix(&data[1..12], 2).unwrap(),
-                    ),
-                    _ => unreachable!(),
-                };
-                let mut sub_packets = Vec::new();
-                match length_type {
-                    LengthType::Zero => {
-                        packet_length_counter += 16;
-                        let mut length_counter = 0;
-                        let end_len = 16 + length;
-                        while length_counter < length {
-                            let sub_packet =
-                                Packet::from_str(&data[16 + length_counter..=end_len]).unwrap();
-                            length_counter += sub_packet.packet_length;
-                            packet_length_counter += sub_packet.packet_length;
-                            sub_packets.push(sub_packet);
-                        }
-                    }
-                    LengthType::One => {
-                        let mut length_counter = 12;
-                        packet_length_counter += 12;
-                        while sub_packets.len() != length {
-                            let sub_packet = Packet::from_str(&data[length_counter..]).unwrap();
-                            length_counter += sub_packet.packet_length;
-                            packet_length_counter += sub_packet.packet_length;
-                            sub_packets.push(sub_packet);
-                        }
+impl Display for Packet {
+    /// Writes the packet as an expression, `sum(min(3, 4), 5)`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.packet_type {
+            PacketType::Literal(value) => write!(f, "{value}"),
+            PacketType::Operator(op, _, sub_packets) => {
+                write!(f, "{op}(")?;
+                for (i, sub_packet) in sub_packets.iter().enumerate() {
+                    if i != 0 {
+                        write!(f, ", ")?;
                     }
+                    write!(f, "{sub_packet}")?;
                 }
-                (Self::Operator(sub_packets), packet_length_counter)
+                write!(f, ")")
             }
         }
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PacketType {
+    Literal(u64),
+    Operator(Operator, LengthType, Vec<Packet>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Sum,
+    Product,
+    Minimum,
+    Maximum,
+    GreaterThan,
+    LessThan,
+    EqualTo,
+}
+
+impl Operator {
+    /// `None` means it's a literal.
+    fn from_id(id: u8) -> Option<Self> {
+        match id {
+            0 => Some(Self::Sum),
+            1 => Some(Self::Product),
+            2 => Some(Self::Minimum),
+            3 => Some(Self::Maximum),
+            4 => None,
+            5 => Some(Self::GreaterThan),
+            6 => Some(Self::LessThan),
+            7 => Some(Self::EqualTo),
+            _ => unreachable!(),
+        }
+    }
+
+    fn id(&self) -> u8 {
+        match self {
+            Operator::Sum => 0,
+            Operator::Product => 1,
+            Operator::Minimum => 2,
+            Operator::Maximum => 3,
+            Operator::GreaterThan => 5,
+            Operator::LessThan => 6,
+            Operator::EqualTo => 7,
+        }
+    }
+}
+
+impl Display for Operator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Operator::Sum => "sum",
+            Operator::Product => "product",
+            Operator::Minimum => "min",
+            Operator::Maximum => "max",
+            Operator::GreaterThan => "gt",
+            Operator::LessThan => "lt",
+            Operator::EqualTo => "eq",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// How the length of the sub packets is given.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum LengthType {
-    Zero,
-    One,
+    /// Total amount of bits
+    Bits,
+    /// Number of sub packets
+    Count,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(hex: &str) -> Packet {
+        Packet::from_str(hex).unwrap()
+    }
+
+    #[test]
+    fn part_1_test() {
+        assert_eq!(parse("D2FE28").packet_type, PacketType::Literal(2021));
+        assert_eq!(parse("8A004A801A8002F478").count_versions(), 16);
+        assert_eq!(parse("620080001611562C8802118E34").count_versions(), 12);
+        assert_eq!(parse("C0015000016115A2E0802F182340").count_versions(), 23);
+        assert_eq!(parse("A0016C880162017C3686B18A3D4780").count_versions(), 31);
+    }
+
+    #[test]
+    fn part_2_test() {
+        for (hex, value) in [
+            ("C200B40A82", 3),
+            ("04005AC33890", 54),
+            ("880086C3E88112", 7),
+            ("CE00C43D881120", 9),
+            ("D8005AC2A8F0", 1),
+            ("F600BC2D8F", 0),
+            ("9C005AC2F8F0", 0),
+            ("9C0141080250320F1802104A08", 1),
+        ] {
+            assert_eq!(parse(hex).calculate_value(), value, "{hex}");
+        }
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(parse("D2FE28").to_string(), "2021");
+        assert_eq!(parse("880086C3E88112").to_string(), "min(7, 8, 9)");
+        assert_eq!(
+            parse("9C0141080250320F1802104A08").to_string(),
+            "eq(sum(1, 3), product(2, 2))"
+        );
+    }
+
+    #[test]
+    fn encode() {
+        for hex in [
+            "D2FE28",
+            "38006F45291200",
+            "EE00D40C823060",
+            "C200B40A82",
+            "04005AC33890",
+            "9C0141080250320F1802104A08",
+        ] {
+            assert_eq!(parse(hex).encode(), hex);
+        }
+    }
+
+    /// Makes a random packet tree, only the first few levels can have operators.
+    fn random_packet(random: &mut impl FnMut() -> u64, depth: usize) -> Packet {
+        let version = (random() % 8) as u8;
+        let packet_type = if depth == 0 || random().is_multiple_of(3) {
+            // Some small numbers and some big ones
+            PacketType::Literal(random() >> (random() % 64))
+        } else {
+            let op = Operator::from_id([0, 1, 2, 3, 5, 6, 7][(random() % 7) as usize]).unwrap();
+            let length_type = if random().is_multiple_of(2) {
+                LengthType::Bits
+            } else {
+                LengthType::Count
+            };
+            let count = 1 + random() % 4;
+            let sub_packets = (0..count)
+                .map(|_| random_packet(random, depth - 1))
+                .collect();
+            PacketType::Operator(op, length_type, sub_packets)
+        };
+        Packet {
+            version,
+            packet_type,
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut seed: u64 = 0x2021_1216;
+        let mut random = || {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed
+        };
+        for _ in 0..500 {
+            let packet = random_packet(&mut random, 4);
+            let hex = packet.encode();
+            let decoded = parse(&hex);
+            assert_eq!(decoded, packet, "{hex}");
+            assert_eq!(decoded.encode(), hex);
+            assert_eq!(decoded.to_string(), packet.to_string());
+        }
+    }
 }
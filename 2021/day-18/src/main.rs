@@ -1,196 +1,324 @@
-use std::fmt::Debug;
+use std::fmt::Display;
 use std::fs::File;
 use std::io::prelude::*;
 use std::io::BufReader;
+use std::ops::Add;
+use std::str::FromStr;
 
 fn main() {
     let input = load_input();
-    let mut first = input[0].clone();
-    for other_number in input[1..].iter().cloned() {
-        add_other_number(&mut first, other_number);
-        reduce_number(&mut first);
-    }
-    println!("Solution for part 1: {}", get_magnitude(first.clone()));
+    println!("Solution for part 1: {}", part_1(&input));
+    println!("Solution for part 2: {}", part_2(&input));
+}
+
+fn part_1(input: &[SnailNumber]) -> u64 {
+    input
+        .iter()
+        .cloned()
+        .reduce(|sum, number| sum + number)
+        .unwrap()
+        .magnitude()
+}
 
+fn part_2(input: &[SnailNumber]) -> u64 {
     let mut highest_magnitude = 0;
-    for first in input.iter().cloned() {
-        for second in input.iter().cloned() {
-            if first == second {
+    for (i, first) in input.iter().enumerate() {
+        for (j, second) in input.iter().enumerate() {
+            if i == j {
                 continue;
             }
-            let mut current_first = first.clone();
-            add_other_number(&mut current_first, second);
-            reduce_number(&mut current_first);
-            let mag = get_magnitude(current_first);
+            let mag = (first.clone() + second.clone()).magnitude();
             if mag > highest_magnitude {
                 highest_magnitude = mag;
             }
         }
     }
-    println!("Solution for part 2: {}", highest_magnitude);
+    highest_magnitude
+}
+
+fn load_input() -> Vec<SnailNumber> {
+    let file = File::open("input").expect("No input file found");
+    let reader = BufReader::new(file);
+    reader
+        .lines()
+        .map(|l| l.unwrap().parse().unwrap())
+        .collect()
 }
 
-/// Gets the magnitude of the number
-///
-/// First gets the biggest depth then looks for numbers at that depth. Since pairs at the biggest depths are always regular numbers
-/// the pairs will always be (index, index + 1). Each pair at that depth gets replaced with a new number at a lower depth with the value
-/// equal to it's magnitude. Once there are no more numbers at the lowest depth, decrement it and do it again. When the biggest depth is 1 it
-/// means there are only 2 numbers left so just calculate their magnitude and return it.
-fn get_magnitude(mut number: Vec<Point>) -> usize {
-    let mut lowest = get_biggest_depth(&number);
-    'main: loop {
-        if lowest == 1 {
-            return (number[0].value * 3) + (number[1].value * 2);
-        }
-        for index in 0..number.len() {
-            if number[index].depth == lowest {
-                let new_num = (number[index].value * 3) + (number[index + 1].value * 2);
-                number.remove(index);
-                number[index] = Point {
-                    value: new_num,
-                    depth: lowest - 1,
-                };
-                continue 'main;
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SnailNumber {
+    Regular(u64),
+    Pair(Box<SnailNumber>, Box<SnailNumber>),
+}
+
+impl SnailNumber {
+    fn pair(left: SnailNumber, right: SnailNumber) -> Self {
+        Self::Pair(Box::new(left), Box::new(right))
+    }
+
+    /// Keeps exploding and splitting until there is nothing left to do
+    fn reduce(&mut self) {
+        while self.explode() || self.split() {}
+    }
+
+    /// Explodes the leftmost pair that is nested inside 4 pairs, returns false if there was none
+    fn explode(&mut self) -> bool {
+        self.explode_at(0).is_some()
+    }
+
+    /// Returns the values that still need to be added to the left and right of the exploded pair
+    fn explode_at(&mut self, depth: usize) -> Option<(Option<u64>, Option<u64>)> {
+        let Self::Pair(left, right) = self else {
+            return None;
+        };
+        if depth >= 4 {
+            if let (Self::Regular(l), Self::Regular(r)) = (left.as_ref(), right.as_ref()) {
+                let exploded = (Some(*l), Some(*r));
+                *self = Self::Regular(0);
+                return Some(exploded);
             }
         }
-        lowest -= 1;
+        if let Some((to_left, to_right)) = left.explode_at(depth + 1) {
+            if let Some(value) = to_right {
+                right.add_to_edge(value, true);
+            }
+            return Some((to_left, None));
+        }
+        if let Some((to_left, to_right)) = right.explode_at(depth + 1) {
+            if let Some(value) = to_left {
+                left.add_to_edge(value, false);
+            }
+            return Some((None, to_right));
+        }
+        None
     }
-}
 
-fn reduce_number(number: &mut Vec<Point>) {
-    'main: loop {
-        // Explode search
-        // Find the first number that can be exploded
-        for index in 0..number.len() {
-            if number[index].depth >= 5 {
-                let left = number[index].value;
-                let right = number[index + 1].value;
-
-                // Explode to left if possible
-                if index > 0 {
-                    number[index - 1].value += left;
-                }
-                // Explode to right if possible
-                if index < number.len() - 2 {
-                    number[index + 2].value += right;
-                }
-
-                // Remove first of pair which moves to next to it's index
-                let old = number.remove(index);
-                // Change second in pair to value 0 with a lower depth
-                number[index] = Point {
-                    value: 0,
-                    depth: old.depth - 1,
-                };
-                // If exploded restart the loop to look for more to explode
-                continue 'main;
-            }
+    /// Adds the value to the leftmost or rightmost regular number
+    fn add_to_edge(&mut self, value: u64, leftmost: bool) {
+        match self {
+            Self::Regular(n) => *n += value,
+            Self::Pair(left, _) if leftmost => left.add_to_edge(value, leftmost),
+            Self::Pair(_, right) => right.add_to_edge(value, leftmost),
         }
+    }
 
-        // Split search
-        // Find first number that can be split
-        // Will only get reached if nothing exploded
-        for index in 0..number.len() {
-            let v = number[index].value;
-            if v >= 10 {
-                // Gets the number to split
-                let old = number.remove(index);
-                let div = v / 2;
-
-                // If not divisible by 2
-                if v % 2 != 0 {
-                    // First of the new pair, rounded down
-                    number.insert(
-                        index,
-                        Point {
-                            value: div,
-                            depth: old.depth + 1,
-                        },
-                    );
-                    // Second of the new pair, rounded up
-                    number.insert(
-                        index + 1,
-                        Point {
-                            value: div + 1,
-                            depth: old.depth + 1,
-                        },
-                    );
-                } else {
-                    // Divisible by 2
-                    number.insert(
-                        index,
-                        Point {
-                            value: div,
-                            depth: old.depth + 1,
-                        },
-                    );
-                    number.insert(
-                        index + 1,
-                        Point {
-                            value: div,
-                            depth: old.depth + 1,
-                        },
-                    );
-                }
-                // If split happened loop again from start
-                continue 'main;
+    /// Splits the leftmost number that is 10 or bigger, returns false if there was none
+    fn split(&mut self) -> bool {
+        match self {
+            Self::Regular(n) if *n >= 10 => {
+                *self = Self::pair(Self::Regular(*n / 2), Self::Regular(n.div_ceil(2)));
+                true
             }
+            Self::Regular(_) => false,
+            Self::Pair(left, right) => left.split() || right.split(),
         }
-        // Will only get reached if no explosion or split happen meaning the number is reduced
-        break;
     }
-}
 
-/// Iterates through points and returns the biggest depth found
-fn get_biggest_depth(input: &[Point]) -> usize {
-    let mut counter = 0;
-    for point in input {
-        if point.depth > counter {
-            counter = point.depth;
+    fn magnitude(&self) -> u64 {
+        match self {
+            Self::Regular(n) => *n,
+            Self::Pair(left, right) => 3 * left.magnitude() + 2 * right.magnitude(),
+        }
+    }
+
+    /// Parses a single number and returns the rest of the string
+    fn parse_part(s: &str) -> Result<(Self, &str), ()> {
+        if let Some(rest) = s.strip_prefix('[') {
+            let (left, rest) = Self::parse_part(rest)?;
+            let rest = rest.strip_prefix(',').ok_or(())?;
+            let (right, rest) = Self::parse_part(rest)?;
+            let rest = rest.strip_prefix(']').ok_or(())?;
+            Ok((Self::pair(left, right), rest))
+        } else {
+            let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+            let value = s[..end].parse().map_err(|_| ())?;
+            Ok((Self::Regular(value), &s[end..]))
         }
     }
-    counter
 }
 
-/// Adds 2 numbers together
-fn add_other_number(first: &mut Vec<Point>, mut second: Vec<Point>) {
-    first.append(&mut second);
-    for point in first {
-        point.depth += 1;
+impl Add for SnailNumber {
+    type Output = SnailNumber;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let mut sum = Self::pair(self, rhs);
+        sum.reduce();
+        sum
     }
 }
 
-fn load_input() -> Vec<Vec<Point>> {
-    let file = File::open("input").expect("No input file found");
-    let reader = BufReader::new(file);
-    let mut input = Vec::new();
-    for line in reader.lines() {
-        let mut arena = Vec::new();
-        let mut depth_counter = 0;
-        for c in line.unwrap().chars() {
-            match c {
-                '[' => depth_counter += 1,
-                ']' => depth_counter -= 1,
-                ',' => {}
-                other => arena.push(Point {
-                    depth: depth_counter,
-                    value: other.to_digit(10).unwrap() as usize,
-                }),
-            }
+impl FromStr for SnailNumber {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match Self::parse_part(s.trim())? {
+            (number, "") => Ok(number),
+            _ => Err(()),
         }
-        input.push(arena);
     }
-    input
 }
 
-#[derive(Clone, PartialEq, Eq)]
-struct Point {
-    value: usize,
-    depth: usize,
+impl Display for SnailNumber {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Regular(n) => write!(f, "{n}"),
+            Self::Pair(left, right) => write!(f, "[{left},{right}]"),
+        }
+    }
 }
 
-impl Debug for Point {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.value)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> SnailNumber {
+        s.parse().unwrap()
+    }
+
+    fn parse_all(lines: &[&str]) -> Vec<SnailNumber> {
+        lines.iter().map(|l| parse(l)).collect()
+    }
+
+    const HOMEWORK: [&str; 10] = [
+        "[[[0,[5,8]],[[1,7],[9,6]]],[[4,[1,2]],[[1,4],2]]]",
+        "[[[5,[2,8]],4],[5,[[9,9],0]]]",
+        "[6,[[[6,2],[5,6]],[[7,6],[4,7]]]]",
+        "[[[6,[0,7]],[0,9]],[4,[9,[9,0]]]]",
+        "[[[7,[6,4]],[3,[1,3]]],[[[5,5],1],9]]",
+        "[[6,[[7,3],[3,2]]],[[[3,8],[5,7]],4]]",
+        "[[[[5,4],[7,7]],8],[[8,3],8]]",
+        "[[9,3],[[9,9],[6,[4,9]]]]",
+        "[[2,[[7,7],7]],[[5,8],[[9,3],[0,2]]]]",
+        "[[[[5,2],5],[8,[3,7]]],[[5,[7,5]],[4,4]]]",
+    ];
+
+    #[test]
+    fn parse_and_display() {
+        for s in
+            HOMEWORK
+                .into_iter()
+                .chain(["[1,2]", "[[1,2],3]", "[[[[0,7],4],[15,[0,13]]],[1,1]]"])
+        {
+            assert_eq!(parse(s).to_string(), s);
+        }
+        assert_eq!(
+            parse("[[1,2],3]"),
+            SnailNumber::pair(
+                SnailNumber::pair(SnailNumber::Regular(1), SnailNumber::Regular(2)),
+                SnailNumber::Regular(3)
+            )
+        );
+        for invalid in ["", "[1,2", "[1 2]", "[1,2]]", "[a,2]"] {
+            assert_eq!(invalid.parse::<SnailNumber>(), Err(()), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn explode() {
+        for (before, after) in [
+            ("[[[[[9,8],1],2],3],4]", "[[[[0,9],2],3],4]"),
+            ("[7,[6,[5,[4,[3,2]]]]]", "[7,[6,[5,[7,0]]]]"),
+            ("[[6,[5,[4,[3,2]]]],1]", "[[6,[5,[7,0]]],3]"),
+            (
+                "[[3,[2,[1,[7,3]]]],[6,[5,[4,[3,2]]]]]",
+                "[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]",
+            ),
+            (
+                "[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]",
+                "[[3,[2,[8,0]]],[9,[5,[7,0]]]]",
+            ),
+        ] {
+            let mut number = parse(before);
+            assert!(number.explode());
+            assert_eq!(number.to_string(), after);
+        }
+        assert!(!parse("[[[[0,9],2],3],4]").explode());
+    }
+
+    #[test]
+    fn split() {
+        let mut number = parse("[[[[0,7],4],[15,[0,13]]],[1,1]]");
+        assert!(number.split());
+        assert_eq!(number.to_string(), "[[[[0,7],4],[[7,8],[0,13]]],[1,1]]");
+        assert!(number.split());
+        assert_eq!(number.to_string(), "[[[[0,7],4],[[7,8],[0,[6,7]]]],[1,1]]");
+        assert!(!parse("[9,[1,2]]").split());
+    }
+
+    #[test]
+    fn reduce_steps() {
+        let mut number = SnailNumber::pair(parse("[[[[4,3],4],4],[7,[[8,4],9]]]"), parse("[1,1]"));
+        assert_eq!(number.to_string(), "[[[[[4,3],4],4],[7,[[8,4],9]]],[1,1]]");
+        let steps = [
+            (true, "[[[[0,7],4],[7,[[8,4],9]]],[1,1]]"),
+            (true, "[[[[0,7],4],[15,[0,13]]],[1,1]]"),
+            (false, "[[[[0,7],4],[[7,8],[0,13]]],[1,1]]"),
+            (false, "[[[[0,7],4],[[7,8],[0,[6,7]]]],[1,1]]"),
+            (true, "[[[[0,7],4],[[7,8],[6,0]]],[8,1]]"),
+        ];
+        for (is_explode, after) in steps {
+            if is_explode {
+                assert!(number.explode());
+            } else {
+                assert!(!number.explode());
+                assert!(number.split());
+            }
+            assert_eq!(number.to_string(), after);
+        }
+        assert!(!number.explode() && !number.split());
+        assert_eq!(
+            parse("[[[[4,3],4],4],[7,[[8,4],9]]]") + parse("[1,1]"),
+            number
+        );
+    }
+
+    #[test]
+    fn add() {
+        let numbers = parse_all(&["[1,1]", "[2,2]", "[3,3]", "[4,4]", "[5,5]", "[6,6]"]);
+        for (count, sum) in [
+            (4, "[[[[1,1],[2,2]],[3,3]],[4,4]]"),
+            (5, "[[[[3,0],[5,3]],[4,4]],[5,5]]"),
+            (6, "[[[[5,0],[7,4]],[5,5]],[6,6]]"),
+        ] {
+            let result = numbers[..count]
+                .iter()
+                .cloned()
+                .reduce(|a, b| a + b)
+                .unwrap();
+            assert_eq!(result.to_string(), sum);
+        }
+    }
+
+    #[test]
+    fn magnitude() {
+        for (number, magnitude) in [
+            ("[[1,2],[[3,4],5]]", 143),
+            ("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]", 1384),
+            ("[[[[1,1],[2,2]],[3,3]],[4,4]]", 445),
+            ("[[[[3,0],[5,3]],[4,4]],[5,5]]", 791),
+            ("[[[[5,0],[7,4]],[5,5]],[6,6]]", 1137),
+            (
+                "[[[[8,7],[7,7]],[[8,6],[7,7]]],[[[0,7],[6,6]],[8,7]]]",
+                3488,
+            ),
+        ] {
+            assert_eq!(parse(number).magnitude(), magnitude, "{number}");
+        }
+    }
+
+    #[test]
+    fn part_1_test() {
+        let input = parse_all(&HOMEWORK);
+        let sum = input.iter().cloned().reduce(|a, b| a + b).unwrap();
+        assert_eq!(
+            sum.to_string(),
+            "[[[[6,6],[7,6]],[[7,7],[7,0]]],[[[7,7],[7,7]],[[7,8],[9,9]]]]"
+        );
+        assert_eq!(part_1(&input), 4140);
+    }
+
+    #[test]
+    fn part_2_test() {
+        assert_eq!(part_2(&parse_all(&HOMEWORK)), 3993);
     }
 }
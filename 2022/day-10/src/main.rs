@@ -7,10 +7,11 @@ fn main() {
     let input = load_input();
     let (count, screen) = solve(&input);
     println!("Part 1: {}", count);
-    println!("Part 2:");
-    for row in screen {
-        println!("{}", String::from_iter(row));
-    }
+    println!("Part 2: {}", part_2(&screen));
+}
+
+fn part_2(screen: &[Vec<char>]) -> String {
+    utils::ocr::recognise_by(screen, |p| *p == '#')
 }
 
 fn solve(input: &[Op]) -> (isize, Vec<Vec<char>>) {
@@ -91,3 +92,24 @@ impl FromStr for Op {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part_2_test() {
+        let screen: Vec<Vec<char>> = [
+            "###  #  #  ##  #### ",
+            "#  # #  # #  # #    ",
+            "#  # #  # #    ###  ",
+            "###  #  # #    #    ",
+            "# #  #  # #  # #    ",
+            "#  #  ##   ##  #### ",
+        ]
+        .iter()
+        .map(|l| l.chars().collect())
+        .collect();
+        assert_eq!(part_2(&screen), "RUCE");
+    }
+}
@@ -1,44 +1,99 @@
 use regex::Regex;
 use std::collections::HashMap;
-use std::collections::HashSet;
 use std::fs::File;
 use std::io::prelude::*;
 use std::io::BufReader;
 
 fn main() {
-    let (valves, _non_zero) = load_input("input");
-    let shortest = shortest_paths(&valves);
-    // 31 instead of 30 because the first valve will get "opened" while it shouldn't
-    println!("{}", traverse(&shortest, 31, 0, "AA", HashSet::new()));
+    let valves = load_input("input");
+    let network = Network::new(&shortest_paths(&valves));
+    println!("Solution for part 1: {}", part_1(&network));
+    println!("Solution for part 2: {}", part_2(&network));
 }
 
-fn traverse(
-    valves: &HashMap<String, Valve>,
-    mut time_left: i64,
-    mut cur_pressure: i64,
-    cur_valve: &str,
-    mut visited: HashSet<String>,
-) -> i64 {
-    if time_left <= 0 {
-        return 0;
+fn part_1(network: &Network) -> i64 {
+    network.best_pressures(30).into_values().max().unwrap()
+}
+
+/// You and the elephant open different valves so the best pair of disjoint sets is the answer.
+fn part_2(network: &Network) -> i64 {
+    let best: Vec<(u64, i64)> = network.best_pressures(26).into_iter().collect();
+    let mut result = 0;
+    for (i, (mask, pressure)) in best.iter().enumerate() {
+        for (other_mask, other_pressure) in &best[i..] {
+            if mask & other_mask == 0 {
+                result = result.max(pressure + other_pressure);
+            }
+        }
+    }
+    result
+}
+
+/// The valves worth opening with their distances, every valve gets a bit in the opened mask.
+#[derive(Debug)]
+struct Network {
+    flows: Vec<i64>,
+    /// `distances[a][b]` is how long it takes to get from valve `a` to `b`
+    distances: Vec<Vec<i64>>,
+    /// Index of `AA`, it doesn't have a flow so it isn't part of `flows`
+    start: usize,
+}
+
+impl Network {
+    fn new(valves: &HashMap<String, Valve>) -> Self {
+        let mut names: Vec<&String> = valves.keys().filter(|n| *n != "AA").collect();
+        names.sort();
+        let start = names.len();
+        names.push(&valves["AA"].name);
+        let flows = names[..start].iter().map(|n| valves[*n].flow).collect();
+        let distances = names
+            .iter()
+            .map(|from| {
+                names[..start]
+                    .iter()
+                    .map(|to| valves[*from].connections.get(*to).copied().unwrap_or(0))
+                    .collect()
+            })
+            .collect();
+        Self {
+            flows,
+            distances,
+            start,
+        }
+    }
+
+    /// The highest pressure that can be released for every set of opened valves.
+    fn best_pressures(&self, time: i64) -> HashMap<u64, i64> {
+        let mut best = HashMap::new();
+        self.traverse(self.start, time, 0, 0, &mut best);
+        best
     }
-    let cur = &valves[cur_valve];
-    time_left -= 1;
-    cur_pressure += cur.flow * time_left;
-    visited.insert(cur_valve.to_string());
-    let mut best = cur_pressure;
-    for (other, time) in cur.connections.iter() {
-        if !visited.contains(other) {
-            best = best.max(traverse(
-                valves,
-                time_left - time,
-                cur_pressure,
+
+    fn traverse(
+        &self,
+        cur_valve: usize,
+        time_left: i64,
+        opened: u64,
+        pressure: i64,
+        best: &mut HashMap<u64, i64>,
+    ) {
+        let entry = best.entry(opened).or_insert(0);
+        *entry = (*entry).max(pressure);
+        for (other, flow) in self.flows.iter().enumerate() {
+            // +1 for opening it
+            let time_left = time_left - self.distances[cur_valve][other] - 1;
+            if opened & (1 << other) != 0 || time_left <= 0 {
+                continue;
+            }
+            self.traverse(
                 other,
-                visited.clone(),
-            ));
+                time_left,
+                opened | (1 << other),
+                pressure + flow * time_left,
+                best,
+            );
         }
     }
-    best
 }
 
 /// Gets the shortest paths between valves that are worth going to
@@ -96,21 +151,16 @@ fn shortest_paths(valves: &[Valve]) -> HashMap<String, Valve> {
     new_valves
 }
 
-fn load_input(name: &str) -> (Vec<Valve>, Vec<String>) {
+fn load_input(name: &str) -> Vec<Valve> {
     let file = File::open(name).expect("No input file found");
     let reader = BufReader::new(file);
     let main_re = Regex::new(r"Valve (\w\w).*=(\d+).*valves? (.*)").unwrap();
     let mut valves = Vec::new();
-    let mut non_zero = Vec::new();
     for line in reader.lines().map(|l| l.unwrap()) {
         let captures = main_re.captures(&line).unwrap();
         let name: String = captures.get(1).unwrap().as_str().into();
         let flow: i64 = captures.get(2).unwrap().as_str().parse().unwrap();
 
-        if flow > 0 {
-            non_zero.push(name.clone());
-        }
-
         let mut connections: HashMap<String, i64> = HashMap::new();
         for con in captures
             .get(3)
@@ -127,7 +177,7 @@ fn load_input(name: &str) -> (Vec<Valve>, Vec<String>) {
             connections,
         });
     }
-    (valves, non_zero)
+    valves
 }
 
 #[derive(Debug)]
@@ -139,7 +189,12 @@ struct Valve {
 
 #[test]
 fn example() {
-    let (valves, _non_zero) = load_input("example");
-    let shortest = shortest_paths(&valves);
-    assert_eq!(traverse(&shortest, 31, 0, "AA", HashSet::new()), 1651);
+    let network = Network::new(&shortest_paths(&load_input("example")));
+    assert_eq!(part_1(&network), 1651);
+}
+
+#[test]
+fn example_part_2() {
+    let network = Network::new(&shortest_paths(&load_input("example")));
+    assert_eq!(part_2(&network), 1707);
 }
@@ -1,15 +1,21 @@
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::prelude::*;
 use std::io::BufReader;
 
 fn main() {
     let (map, moves) = load_input("input");
-    println!("{}", part_1(&map, &moves));
+    println!("Solution for part 1: {}", part_1(&map, &moves));
+    println!("Solution for part 2: {}", part_2(&map, &moves));
 }
 
 fn part_1(map: &[Vec<Tile>], moves: &[Move]) -> usize {
-    let result = solve(map, moves);
-    (result.x * 1000) + (result.y * 4) + result.orientation
+    solve(map, moves, |position| get_target(position, map)).password()
+}
+
+fn part_2(map: &[Vec<Tile>], moves: &[Move]) -> usize {
+    let cube = Cube::new(map);
+    solve(map, moves, |position| cube.get_target(position, map)).password()
 }
 
 #[allow(dead_code)]
@@ -26,21 +32,23 @@ fn print_map(map: &[Vec<Tile>]) {
     }
 }
 
-fn solve(map: &[Vec<Tile>], moves: &[Move]) -> Position {
+/// `get_target` gives the position after a step forward, including the orientation in case it
+/// changes when wrapping around.
+fn solve<F>(map: &[Vec<Tile>], moves: &[Move], get_target: F) -> Position
+where
+    F: Fn(&Position) -> Position,
+{
     let mut position = find_start(map);
 
     for m in moves {
         match m {
             Move::Left => position.rotate_left(),
             Move::Right => position.rotate_right(),
-            Move::Move(count) => {
+            Move::Forward(count) => {
                 for _ in 0..*count {
-                    let target = get_target(&position, map);
-                    match map[target.0][target.1] {
-                        Tile::Empty => {
-                            position.x = target.0;
-                            position.y = target.1;
-                        }
+                    let target = get_target(&position);
+                    match map[target.x][target.y] {
+                        Tile::Empty => position = target,
                         Tile::Wall => break,
                         Tile::Void => unreachable!(),
                     }
@@ -52,14 +60,9 @@ fn solve(map: &[Vec<Tile>], moves: &[Move]) -> Position {
     position
 }
 
-fn get_target(position: &Position, map: &[Vec<Tile>]) -> (usize, usize) {
-    let (mut i, mut j) = match position.orientation {
-        0 => (position.x, position.y + 1),
-        1 => (position.x + 1, position.y),
-        2 => (position.x, position.y - 1),
-        3 => (position.x - 1, position.y),
-        _ => unreachable!(),
-    };
+/// Wraps around to the other side of the map.
+fn get_target(position: &Position, map: &[Vec<Tile>]) -> Position {
+    let (mut i, mut j) = position.forward();
     if !map[i][j].not_void() {
         match position.orientation {
             0 => {
@@ -105,7 +108,175 @@ fn get_target(position: &Position, map: &[Vec<Tile>]) -> (usize, usize) {
             _ => unreachable!(),
         }
     }
-    (i, j)
+    Position {
+        x: i,
+        y: j,
+        orientation: position.orientation,
+    }
+}
+
+type Vec3 = [i8; 3];
+
+fn neg(v: Vec3) -> Vec3 {
+    v.map(|x| -x)
+}
+
+/// Where a face of the net ends up after folding it into a cube.
+#[derive(Debug, Clone, Copy)]
+struct Face {
+    /// Points out of the cube
+    normal: Vec3,
+    /// Direction of going right on the map
+    right: Vec3,
+    /// Direction of going down on the map
+    down: Vec3,
+}
+
+impl Face {
+    /// The face next to this one on the map in the given direction.
+    fn fold(&self, orientation: usize) -> Self {
+        let Face {
+            normal,
+            right,
+            down,
+        } = *self;
+        match orientation {
+            0 => Face {
+                normal: right,
+                right: neg(normal),
+                down,
+            },
+            1 => Face {
+                normal: down,
+                right,
+                down: neg(normal),
+            },
+            2 => Face {
+                normal: neg(right),
+                right: normal,
+                down,
+            },
+            3 => Face {
+                normal: neg(down),
+                right,
+                down: normal,
+            },
+            _ => unreachable!(),
+        }
+    }
+
+    /// The direction of the orientation in 3D.
+    fn direction(&self, orientation: usize) -> Vec3 {
+        match orientation {
+            0 => self.right,
+            1 => self.down,
+            2 => neg(self.right),
+            3 => neg(self.down),
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// The map folded into a cube.
+#[derive(Debug)]
+struct Cube {
+    size: usize,
+    /// Faces by their (row, column) on the net
+    faces: HashMap<(usize, usize), Face>,
+}
+
+impl Cube {
+    /// Works out the size of the faces and folds the net by walking from face to face.
+    fn new(map: &[Vec<Tile>]) -> Self {
+        let area = map.iter().flatten().filter(|t| t.not_void()).count();
+        let size = (1..).find(|s| s * s * 6 >= area).unwrap();
+        assert_eq!(size * size * 6, area, "Not a cube net");
+
+        let on_net = |(row, col): (usize, usize)| {
+            map.get(1 + row * size)
+                .and_then(|r| r.get(1 + col * size))
+                .is_some_and(|t| t.not_void())
+        };
+        let start = (0, (0..).find(|&col| on_net((0, col))).unwrap());
+        let mut faces = HashMap::from([(
+            start,
+            Face {
+                normal: [0, 0, 1],
+                right: [1, 0, 0],
+                down: [0, 1, 0],
+            },
+        )]);
+        let mut stack = vec![start];
+        while let Some((row, col)) = stack.pop() {
+            let face = faces[&(row, col)];
+            for orientation in 0..4 {
+                let next = match orientation {
+                    0 => (row, col + 1),
+                    1 => (row + 1, col),
+                    2 if col > 0 => (row, col - 1),
+                    3 if row > 0 => (row - 1, col),
+                    _ => continue,
+                };
+                if on_net(next) && !faces.contains_key(&next) {
+                    faces.insert(next, face.fold(orientation));
+                    stack.push(next);
+                }
+            }
+        }
+        assert_eq!(faces.len(), 6, "Not a cube net");
+        Self { size, faces }
+    }
+
+    /// Moves over the edge of the cube if there is nothing in front on the map.
+    fn get_target(&self, position: &Position, map: &[Vec<Tile>]) -> Position {
+        let (i, j) = position.forward();
+        if map[i][j].not_void() {
+            return Position {
+                x: i,
+                y: j,
+                orientation: position.orientation,
+            };
+        }
+
+        let s = self.size;
+        let (row, col) = ((position.x - 1) / s, (position.y - 1) / s);
+        let (i, j) = ((position.x - 1) % s, (position.y - 1) % s);
+        let face = self.faces[&(row, col)];
+        // Distance along the edge that is being crossed and which way it's counted in 3D
+        let (along, edge) = match position.orientation {
+            0 | 2 => (i, face.down),
+            _ => (j, face.right),
+        };
+
+        let exit = face.direction(position.orientation);
+        let (&(new_row, new_col), new_face) =
+            self.faces.iter().find(|(_, f)| f.normal == exit).unwrap();
+        // Going into the face is going away from the one that was left
+        let orientation = (0..4)
+            .find(|&o| new_face.direction(o) == neg(face.normal))
+            .unwrap();
+        let new_edge = match orientation {
+            0 | 2 => new_face.down,
+            _ => new_face.right,
+        };
+        let along = if new_edge == edge {
+            along
+        } else {
+            s - 1 - along
+        };
+        let (i, j) = match orientation {
+            0 => (along, 0),
+            1 => (0, along),
+            2 => (along, s - 1),
+            3 => (s - 1, along),
+            _ => unreachable!(),
+        };
+        Position {
+            x: 1 + new_row * s + i,
+            y: 1 + new_col * s + j,
+            orientation,
+        }
+    }
 }
 
 fn find_start(map: &[Vec<Tile>]) -> Position {
@@ -149,7 +320,7 @@ fn load_input(name: &str) -> (Vec<Vec<Tile>>, Vec<Move>) {
                     cur_num.push(c);
                 } else {
                     if !cur_num.is_empty() {
-                        moves.push(Move::Move(cur_num.parse().unwrap()));
+                        moves.push(Move::Forward(cur_num.parse().unwrap()));
                         cur_num = String::new();
                     }
                     moves.push(match c {
@@ -160,7 +331,7 @@ fn load_input(name: &str) -> (Vec<Vec<Tile>>, Vec<Move>) {
                 }
             }
             if !cur_num.is_empty() {
-                moves.push(Move::Move(cur_num.parse().unwrap()));
+                moves.push(Move::Forward(cur_num.parse().unwrap()));
             }
         }
     }
@@ -204,10 +375,10 @@ impl Tile {
 enum Move {
     Left,
     Right,
-    Move(u64),
+    Forward(u64),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 struct Position {
     x: usize,
     y: usize,
@@ -222,6 +393,22 @@ struct Position {
 }
 
 impl Position {
+    /// The map has a border so the positions already start at 1.
+    fn password(&self) -> usize {
+        (self.x * 1000) + (self.y * 4) + self.orientation
+    }
+
+    /// The tile in front.
+    fn forward(&self) -> (usize, usize) {
+        match self.orientation {
+            0 => (self.x, self.y + 1),
+            1 => (self.x + 1, self.y),
+            2 => (self.x, self.y - 1),
+            3 => (self.x - 1, self.y),
+            _ => unreachable!(),
+        }
+    }
+
     fn rotate_right(&mut self) {
         self.orientation = (self.orientation + 1) % 4
     }
@@ -236,3 +423,84 @@ fn example() {
     let (map, moves) = load_input("example");
     assert_eq!(part_1(&map, &moves), 6032);
 }
+
+#[test]
+fn example_part_2() {
+    let (map, moves) = load_input("example");
+    let cube = Cube::new(&map);
+    assert_eq!(cube.size, 4);
+    // Walking off the right of the face next to the start ends up on the bottom right face going
+    // down, as shown in the example
+    assert_eq!(
+        cube.get_target(
+            &Position {
+                x: 6,
+                y: 12,
+                orientation: 0
+            },
+            &map
+        ),
+        Position {
+            x: 9,
+            y: 15,
+            orientation: 1
+        }
+    );
+    assert_eq!(part_2(&map, &moves), 5031);
+}
+
+/// Every one of the 11 nets should fold so that walking over an edge and back ends up in the same
+/// place.
+#[test]
+fn all_nets() {
+    let nets = [
+        ["#...", "####", "#..."],
+        ["#...", "####", ".#.."],
+        ["#...", "####", "..#."],
+        ["#...", "####", "...#"],
+        [".#..", "####", ".#.."],
+        [".#..", "####", "..#."],
+        ["##..", ".###", ".#.."],
+        ["##..", ".###", "..#."],
+        ["##..", ".###", "...#"],
+        ["###..", "..###", "....."],
+        ["##..", ".##.", "..##"],
+    ];
+    let size = 3;
+    for net in nets {
+        let mut map = vec![vec![Tile::Void; 5 * size + 2]; 3 * size + 2];
+        for (row, line) in net.iter().enumerate() {
+            for (col, c) in line.chars().enumerate() {
+                if c == '#' {
+                    for x in 0..size {
+                        for y in 0..size {
+                            map[1 + row * size + x][1 + col * size + y] = Tile::Empty;
+                        }
+                    }
+                }
+            }
+        }
+        let cube = Cube::new(&map);
+        let mut normals: Vec<Vec3> = cube.faces.values().map(|f| f.normal).collect();
+        normals.sort();
+        normals.dedup();
+        assert_eq!(normals.len(), 6, "{net:?}");
+
+        for x in 0..map.len() {
+            for y in 0..map[0].len() {
+                if !map[x][y].not_void() {
+                    continue;
+                }
+                for orientation in 0..4 {
+                    let start = Position { x, y, orientation };
+                    let mut target = cube.get_target(&start, &map);
+                    assert!(map[target.x][target.y].not_void(), "{net:?} {start:?}");
+                    target.orientation = (target.orientation + 2) % 4;
+                    let mut back = cube.get_target(&target, &map);
+                    back.orientation = (back.orientation + 2) % 4;
+                    assert_eq!(back, start, "{net:?}");
+                }
+            }
+        }
+    }
+}
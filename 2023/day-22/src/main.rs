@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::collections::VecDeque;
 use std::fs::File;
 use std::io::prelude::*;
 use std::io::BufReader;
@@ -7,34 +8,82 @@ use std::io::BufReader;
 type Brick = ((usize, usize, usize), (usize, usize, usize));
 
 fn main() {
-    let input = load_input("input");
-    println!("Solution for part 1: {}", part_1(&input.0, input.1, input.2));
+    let (bricks, max_x, max_y) = load_input("input");
+    let graph = SupportGraph::new(&bricks, max_x, max_y);
+    println!("Solution for part 1: {}", part_1(&graph));
+    println!("Solution for part 2: {}", part_2(&graph));
 }
 
-fn part_1(bricks: &[Brick], max_x: usize, max_y: usize) -> usize {
-    let (supported_by, supporting_map) = simulate_falling(bricks, max_x, max_y);
+fn part_1(graph: &SupportGraph) -> usize {
+    graph.ids().filter(|&id| graph.can_disintegrate(id)).count()
+}
 
-    let mut counter = 0;
+fn part_2(graph: &SupportGraph) -> usize {
+    graph.ids().map(|id| graph.chain_reaction(id).len()).sum()
+}
 
-    'main: for id in 1..=bricks.len() {
-        if id == 0 {
-            continue;
+/// Which bricks are resting on which after all of them fell.
+///
+/// Bricks are numbered starting from 1 in the order they fell, 0 is the ground.
+#[derive(Debug)]
+struct SupportGraph {
+    count: usize,
+    supported_by: HashMap<usize, HashSet<usize>>,
+    supporting: HashMap<usize, HashSet<usize>>,
+}
+
+impl SupportGraph {
+    fn new(bricks: &[Brick], max_x: usize, max_y: usize) -> Self {
+        let (supported_by, supporting) = simulate_falling(bricks, max_x, max_y);
+        Self {
+            count: bricks.len(),
+            supported_by,
+            supporting,
         }
-        if let Some(supported_bricks) = supporting_map.get(&id) {
-            for t in supported_bricks {
-                match supported_by.get(t) {
-                    Some(x) => {
-                        if x.len() <= 1 {
-                            continue 'main;
-                        }
-                    }
-                    None => continue 'main,
+    }
+
+    fn ids(&self) -> impl Iterator<Item = usize> {
+        1..=self.count
+    }
+
+    /// The bricks resting on top of the brick.
+    fn supports(&self, id: usize) -> impl Iterator<Item = usize> + '_ {
+        self.supporting.get(&id).into_iter().flatten().copied()
+    }
+
+    /// The bricks the brick is resting on, 0 if it's on the ground.
+    fn supported_by(&self, id: usize) -> impl Iterator<Item = usize> + '_ {
+        self.supported_by.get(&id).into_iter().flatten().copied()
+    }
+
+    /// True if all of the bricks on top of it are also resting on something else.
+    fn can_disintegrate(&self, id: usize) -> bool {
+        self.supports(id)
+            .all(|other| self.supported_by(other).count() > 1)
+    }
+
+    /// The other bricks that fall if the brick gets disintegrated.
+    ///
+    /// Every brick keeps count of how many of its supports are still there and falls once there
+    /// are none left, so every brick gets checked at most once per support.
+    fn chain_reaction(&self, id: usize) -> Vec<usize> {
+        let mut remaining: HashMap<usize, usize> = HashMap::new();
+        let mut fallen = Vec::new();
+        let mut queue = VecDeque::from([id]);
+        while let Some(cur) = queue.pop_front() {
+            for other in self.supports(cur) {
+                let left = remaining
+                    .entry(other)
+                    .or_insert_with(|| self.supported_by(other).count());
+                *left -= 1;
+                if *left == 0 {
+                    fallen.push(other);
+                    queue.push_back(other);
                 }
             }
         }
-        counter += 1;
+        fallen
     }
-    counter
 }
 
 #[allow(clippy::needless_range_loop)]
@@ -112,14 +161,36 @@ fn load_input(name: &str) -> (Vec<Brick>, usize, usize) {
 mod tests {
     use super::*;
 
+    fn example_graph() -> SupportGraph {
+        let (bricks, max_x, max_y) = load_input("example");
+        SupportGraph::new(&bricks, max_x, max_y)
+    }
+
     #[test]
     fn part_1() {
-        let input = load_input("example");
-        assert_eq!(crate::part_1(&input.0, input.1, input.2), 5);
+        assert_eq!(crate::part_1(&example_graph()), 5);
     }
 
     #[test]
     fn part_2() {
-        let input = load_input("example");
+        assert_eq!(crate::part_2(&example_graph()), 7);
+    }
+
+    #[test]
+    fn support_graph() {
+        let graph = example_graph();
+        let sorted = |mut v: Vec<usize>| {
+            v.sort();
+            v
+        };
+        // A is on the ground and holds up B and C
+        assert_eq!(sorted(graph.supported_by(1).collect()), [0]);
+        assert_eq!(sorted(graph.supports(1).collect()), [2, 3]);
+        assert_eq!(sorted(graph.supported_by(4).collect()), [2, 3]);
+        assert!(!graph.can_disintegrate(1));
+        assert!(graph.can_disintegrate(2));
+        assert_eq!(sorted(graph.chain_reaction(1)), [2, 3, 4, 5, 6, 7]);
+        assert_eq!(graph.chain_reaction(6), [7]);
+        assert_eq!(graph.chain_reaction(7), []);
     }
 }
@@ -1,11 +1,14 @@
-use std::collections::HashSet;
 use std::fs::File;
 use std::io::prelude::*;
 use std::io::BufReader;
 
+use utils::cursor::Direction;
+use utils::graph::Graph;
+
 fn main() {
     let input = load_input("input");
     println!("Solution for part 1: {}", part_1(&input));
+    println!("Solution for part 2: {}", part_2(&input));
 }
 
 fn part_1(map: &[Vec<char>]) -> usize {
@@ -16,59 +19,36 @@ fn part_2(map: &[Vec<char>]) -> usize {
     find_longest_path(map, (1, 1), (map[0].len() - 2, map.len() - 1), true)
 }
 
+/// Turns the map into a graph of the junctions and finds the longest path in it.
 fn find_longest_path(
     map: &[Vec<char>],
     start: (usize, usize),
     end: (usize, usize),
     no_slopes: bool,
 ) -> usize {
-    let mut cur_lonest = 0;
-    let mut q = vec![Ele::new(start.0, start.1)];
-
-    while let Some(cur) = q.pop() {
-        if cur.x == end.0 && cur.y == end.1 {
-            cur_lonest = cur_lonest.max(cur.cur_dist);
-            continue;
+    let graph = Graph::from_grid(map, |from, to, direction| {
+        if *from == '#' || *to == '#' {
+            return false;
         }
-        let mut cur_char = map[cur.y][cur.x];
         if no_slopes {
-            cur_char = '.';
-        }
-
-        let mut neighbors: Vec<(usize, usize)> = Vec::new();
-        match cur_char {
-            '^' => {
-                neighbors.push((cur.x, cur.y - 1));
-            }
-            '>' => {
-                neighbors.push((cur.x + 1, cur.y));
-            }
-            'v' => {
-                neighbors.push((cur.x, cur.y + 1));
-            }
-            '<' => {
-                neighbors.push((cur.x - 1, cur.y));
-            }
-            '.' => {
-                for (new_x, new_y) in [
-                    (cur.x + 1, cur.y),
-                    (cur.x - 1, cur.y),
-                    (cur.x, cur.y + 1),
-                    (cur.x, cur.y - 1),
-                ] {
-                    neighbors.push((new_x, new_y));
-                }
-            }
-            _ => unreachable!(),
+            return true;
         }
-
-        for (new_x, new_y) in neighbors {
-            if !cur.visited.contains(&(new_x, new_y)) && map[new_y][new_x] != '#' {
-                q.push(Ele::new_from(&cur, new_x, new_y));
-            }
+        match from {
+            '^' => direction == Direction::N,
+            '>' => direction == Direction::E,
+            'v' => direction == Direction::S,
+            '<' => direction == Direction::W,
+            _ => true,
         }
-    }
-    cur_lonest + 1
+    });
+    let junctions = graph.contract(|&p| p == start || p == end);
+    let longest = junctions
+        .longest_path(
+            junctions.index_of(&start).unwrap(),
+            junctions.index_of(&end).unwrap(),
+        )
+        .unwrap();
+    longest + 1
 }
 
 fn load_input(name: &str) -> Vec<Vec<char>> {
@@ -82,38 +62,6 @@ fn load_input(name: &str) -> Vec<Vec<char>> {
     input
 }
 
-#[derive(Debug, Clone)]
-struct Ele {
-    x: usize,
-    y: usize,
-    cur_dist: usize,
-    visited: HashSet<(usize, usize)>,
-}
-
-impl Ele {
-    fn new(x: usize, y: usize) -> Self {
-        let mut visited = HashSet::new();
-        visited.insert((x, y));
-        Self {
-            x,
-            y,
-            cur_dist: 0,
-            visited,
-        }
-    }
-
-    fn new_from(other: &Self, x: usize, y: usize) -> Self {
-        let mut visited = other.visited.clone();
-        visited.insert((x, y));
-        Self {
-            x,
-            y,
-            cur_dist: other.cur_dist + 1,
-            visited,
-        }
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,3 +1,41 @@
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::BufReader;
+
+use utils::graph::Graph;
+
 fn main() {
-    println!("Hello, world!");
+    let input = load_input("input");
+    println!("Solution for part 1: {}", part_1(&input));
+}
+
+/// Cuts the 3 wires and multiplies the sizes of the 2 groups.
+fn part_1(graph: &Graph<String>) -> usize {
+    let (cut, group) = graph.min_cut();
+    assert_eq!(cut, 3, "The input should need exactly 3 wires cut");
+    group.len() * (graph.len() - group.len())
+}
+
+fn load_input(name: &str) -> Graph<String> {
+    let file = File::open(name).unwrap_or_else(|_| panic!("No \"{}\" file found", name));
+    let reader = BufReader::new(file);
+    let mut graph = Graph::new();
+    for line in reader.lines().map(|l| l.unwrap()) {
+        let (from, rest) = line.split_once(": ").unwrap();
+        for to in rest.split(' ') {
+            graph.add_edge(from.to_string(), to.to_string(), 1);
+        }
+    }
+    graph
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part_1() {
+        let input = load_input("example");
+        assert_eq!(crate::part_1(&input), 54);
+    }
 }
@@ -1,7 +1,8 @@
 use std::fs::File;
 use std::io::BufReader;
 
-use utils::{divmod, lcm, BufReadExt};
+use utils::linalg::solve_2x2;
+use utils::BufReadExt;
 
 /// ((AX, AY), (BX, BY), (PX, PY))
 type Machine = ((usize, usize), (usize, usize), (usize, usize));
@@ -59,34 +60,15 @@ fn part_2(input: &[Machine]) -> usize {
 fn solve_math(machine: Machine) -> Option<(usize, usize)> {
     let ((a_x, a_y), (b_x, b_y), (target_x, target_y)) = machine;
 
-    let a_lcm = lcm(a_x, a_y);
+    // If there is no whole number solution then it's not possible to get there
+    let [a_value, b_value] = solve_2x2(
+        [[a_x as i128, b_x as i128], [a_y as i128, b_y as i128]],
+        [target_x as i128, target_y as i128],
+    )
+    .ok()?;
 
-    // Removes A from both of the equations
-    let eq_1 = ((a_lcm / a_x) * b_x, (a_lcm / a_x) * target_x);
-    let eq_2 = ((a_lcm / a_y) * b_y, (a_lcm / a_y) * target_y);
-
-    // Subtracts the equations so you end up with
-    // B * BX = XXXX
-    // Where XXXX is some regular number
-    let cur = (eq_1.0.abs_diff(eq_2.0), eq_1.1.abs_diff(eq_2.1));
-
-    // Divides both sides by BX to get B = XXXX
-    let (b_value, rem) = divmod(cur.1, cur.0);
-
-    // If it doesn't divide cleanly then it's not a whole number so we can't get there
-    if rem != 0 {
-        return None;
-    };
-
-    // Replace B with the actual value in one of the equations and solve it to get A
-    let cur = (a_x, target_x - (b_value * b_x));
-
-    let (a_value, rem) = divmod(cur.1, cur.0);
-
-    if rem != 0 {
-        return None;
-    };
-    Some((a_value, b_value))
+    // Can't press a button a negative amount of times
+    Some((a_value.try_into().ok()?, b_value.try_into().ok()?))
 }
 
 fn load_input(name: &str) -> Vec<Machine> {
@@ -4,12 +4,12 @@ use std::fs::File;
 use std::io::BufReader;
 use std::str::FromStr;
 
-use utils::{print_grid, BufReadExt};
+use utils::BufReadExt;
 
 fn main() {
     let input = load_input("input");
     println!("Solution for part 1: {}", part_1(&input, 101, 103));
-    part_2(&input, 101, 103);
+    println!("Solution for part 2: {}", part_2(&input, 101, 103));
 }
 
 fn part_1(robots: &[Robot], x_size: isize, y_size: isize) -> usize {
@@ -28,16 +28,39 @@ fn part_1(robots: &[Robot], x_size: isize, y_size: isize) -> usize {
     counters.0 * counters.1 * counters.2 * counters.3
 }
 
-fn part_2(robots: &[Robot], x_size: isize, y_size: isize) {
-    for i in 0..=(x_size * y_size) {
-        println!("Iteration: {i}");
-        let mut grid = vec![vec![' '; x_size as usize]; y_size as usize];
-        for mut robot in robots.iter().cloned() {
-            robot.simulate(x_size, y_size, i);
-            grid[robot.y as usize][robot.x as usize] = 'X';
-        }
-        print_grid(&grid);
-    }
+/// Finds the first second where the robots form the Christmas tree.
+///
+/// The robots are the most bunched up when they form the picture. The x positions repeat every
+/// `x_size` seconds and the y ones every `y_size` seconds so both can be checked separately for the
+/// second where they are the most bunched up and then combined with the Chinese remainder theorem.
+fn part_2(robots: &[Robot], x_size: isize, y_size: isize) -> isize {
+    let best_x = most_bunched(x_size, |t| robots.iter().map(move |r| r.x + r.v_x * t));
+    let best_y = most_bunched(y_size, |t| robots.iter().map(move |r| r.y + r.v_y * t));
+    (0..y_size)
+        .map(|k| best_x + k * x_size)
+        .find(|t| t % y_size == best_y)
+        .expect("Sizes should be coprime")
+}
+
+/// Returns the second in `0..size` where the positions have the lowest variance.
+fn most_bunched<F, I>(size: isize, positions: F) -> isize
+where
+    F: Fn(isize) -> I,
+    I: Iterator<Item = isize>,
+{
+    (0..size)
+        .min_by_key(|&t| {
+            let (mut n, mut sum, mut sum_squared) = (0, 0, 0);
+            for pos in positions(t) {
+                let pos = pos.rem_euclid(size);
+                n += 1;
+                sum += pos;
+                sum_squared += pos * pos;
+            }
+            // Variance multiplied by n^2 so it stays an integer
+            n * sum_squared - sum * sum
+        })
+        .unwrap()
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -99,4 +122,42 @@ mod tests {
         let robots = load_input("example");
         assert_eq!(part_1(&robots, 11, 7), 12);
     }
+
+    #[test]
+    fn part_2_test() {
+        let mut seed: u64 = 0x2024_1214;
+        let mut random = |max: isize| {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            (seed % max as u64) as isize
+        };
+        let (x_size, y_size, tree_time) = (101, 103, 6_532);
+
+        // A filled triangle that appears after `tree_time` seconds and some robots that don't take
+        // part in it
+        let mut robots = vec![];
+        for y in 0..15 {
+            for x in (15 - y)..(15 + y) {
+                let (v_x, v_y) = (random(201) - 100, random(201) - 100);
+                let mut robot = Robot {
+                    x: 40 + x,
+                    y: 30 + y,
+                    v_x,
+                    v_y,
+                };
+                robot.simulate(x_size, y_size, -tree_time);
+                robots.push(robot);
+            }
+        }
+        for _ in 0..100 {
+            robots.push(Robot {
+                x: random(x_size),
+                y: random(y_size),
+                v_x: random(201) - 100,
+                v_y: random(201) - 100,
+            });
+        }
+        assert_eq!(part_2(&robots, x_size, y_size), tree_time);
+    }
 }
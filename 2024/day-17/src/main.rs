@@ -1,208 +1,376 @@
 use std::fs::File;
 use std::io::BufReader;
 
+use utils::vm::{Flow, Instruction, Machine};
 use utils::BufReadExt;
 
 fn main() {
     let input = load_input("input");
     println!("Solution for part 1: {}", part_1(&input));
-    println!("Solution for part 2: {}", part_2(&input));
+    match part_2(&input) {
+        Ok(solution) => println!("Solution for part 2: {}", solution),
+        Err(e) => println!(
+            "Can't solve part 2: {}\n{}",
+            e,
+            disassemble(input.machine.program())
+        ),
+    }
 }
 
-fn part_2(start: &Computer) -> usize {
+/// Finds the lowest value of register A that makes the program output itself.
+///
+/// [`analyse()`][analyse] checks that every loop outputs a number and then shifts A right by the
+/// same amount. That means the last output only depends on the highest bits of A so the value can
+/// be built up from the end of the program a few bits at a time.
+fn part_2(start: &Computer) -> Result<i64, AnalysisError> {
+    let shift = analyse(start.machine.program())?;
     let mut solutions = vec![];
     let mut q = vec![(0, 1)];
 
     while let Some((cur_number, cur_pos)) = q.pop() {
-        for x in 0..8 {
+        for x in 0..(1 << shift) {
             let v = cur_number + x;
-            let solved = Computer::shortcut(start, v);
-            if solved.result[..] == solved.program[(solved.program.len() - cur_pos)..] {
-                if solved.result.len() == solved.program.len() {
+            let result = start.run_with(v).output;
+            if result[..] == start.program[(start.program.len() - cur_pos)..] {
+                if result.len() == start.program.len() {
                     solutions.push(v);
                     continue;
                 }
-                q.push((v << 3, cur_pos + 1))
+                q.push((v << shift, cur_pos + 1))
             }
         }
     }
-    *solutions.iter().min().unwrap()
+    solutions.into_iter().min().ok_or(AnalysisError::NoSolution)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AnalysisError {
+    /// The program has to end with `jnz 0`.
+    NoLoop,
+    /// There is a jump somewhere else than at the end.
+    ExtraJump(usize),
+    /// The loop has to output exactly 1 number.
+    Outputs(usize),
+    /// The loop has to shift A exactly once.
+    Shifts(usize),
+    /// A is shifted by a register or by 0.
+    BadShift(Combo),
+    /// B or C is used before the loop sets it so the output depends on the previous loop.
+    ReadBeforeWrite(usize, char),
+    /// The program uses combo operand 7.
+    Reserved(usize),
+    /// The program can't output itself.
+    NoSolution,
+}
+
+impl std::fmt::Display for AnalysisError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AnalysisError::NoLoop => write!(f, "the program doesn't end with \"jnz 0\""),
+            AnalysisError::ExtraJump(pc) => write!(f, "there is a jump at {}", pc * 2),
+            AnalysisError::Outputs(count) => write!(f, "the loop outputs {count} numbers, not 1"),
+            AnalysisError::Shifts(count) => write!(f, "the loop changes A {count} times, not 1"),
+            AnalysisError::BadShift(combo) => write!(f, "A is shifted by {combo}"),
+            AnalysisError::ReadBeforeWrite(pc, reg) => {
+                write!(f, "{reg} is used at {} before being set", pc * 2)
+            }
+            AnalysisError::Reserved(pc) => write!(f, "the reserved operand is used at {}", pc * 2),
+            AnalysisError::NoSolution => write!(f, "no value of A makes the program output itself"),
+        }
+    }
+}
+
+/// Checks that the program is a single loop that works through A a few bits at a time and returns
+/// by how many bits A is shifted every loop.
+///
+/// The program has to:
+/// - end with `jnz 0` and have no other jumps
+/// - output exactly 1 number per loop
+/// - shift A only once per loop and only by a literal
+/// - set B and C from A before using them, so nothing carries over between loops
+fn analyse(program: &[Op]) -> Result<u32, AnalysisError> {
+    let (last, body) = program.split_last().ok_or(AnalysisError::NoLoop)?;
+    if *last != Op::Jnz(0) {
+        return Err(AnalysisError::NoLoop);
+    }
+
+    let mut outputs = 0;
+    let mut shifts = vec![];
+    let mut set = vec!['A'];
+    for (pc, op) in body.iter().enumerate() {
+        if op.operand() == Some(Combo::Reserved) {
+            return Err(AnalysisError::Reserved(pc));
+        }
+        if let Some(reg) = op.reads().into_iter().find(|r| !set.contains(r)) {
+            return Err(AnalysisError::ReadBeforeWrite(pc, reg));
+        }
+        match op {
+            Op::Jnz(_) => return Err(AnalysisError::ExtraJump(pc)),
+            Op::Out(_) => outputs += 1,
+            Op::Adv(combo) => shifts.push(*combo),
+            _ => (),
+        }
+        set.extend(op.writes());
+    }
+
+    if outputs != 1 {
+        return Err(AnalysisError::Outputs(outputs));
+    }
+    match shifts[..] {
+        [Combo::Literal(by @ 1..)] => Ok(by as u32),
+        [combo] => Err(AnalysisError::BadShift(combo)),
+        _ => Err(AnalysisError::Shifts(shifts.len())),
+    }
+}
+
+/// Turns the program into text, one instruction per line with the address in front.
+fn disassemble(program: &[Op]) -> String {
+    program
+        .iter()
+        .enumerate()
+        .map(|(pc, op)| format!("{:>2}: {}\n", pc * 2, op))
+        .collect()
 }
 
 fn part_1(computer: &Computer) -> String {
-    let mut cur = computer.clone();
-    cur.run_program();
-    cur.get_result()
+    get_result(&computer.run().output)
+}
+
+fn get_result(output: &[i64]) -> String {
+    output
+        .iter()
+        .map(|x| x.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
 }
 
 fn load_input(name: &str) -> Computer {
     let file = File::open(name).unwrap_or_else(|_| panic!("No \"{}\" file found", name));
     let reader = BufReader::new(file);
     let mut lines = reader.lines_unwrap();
-    Computer {
-        reg_a: lines
-            .next()
-            .unwrap()
-            .strip_prefix("Register A: ")
-            .unwrap()
-            .parse()
-            .unwrap(),
-        reg_b: lines
+    let mut register = |name: &str| -> i64 {
+        lines
             .next()
             .unwrap()
-            .strip_prefix("Register B: ")
+            .strip_prefix(&format!("Register {name}: "))
             .unwrap()
             .parse()
-            .unwrap(),
-        reg_c: lines
-            .next()
-            .unwrap()
-            .strip_prefix("Register C: ")
-            .unwrap()
-            .parse()
-            .unwrap(),
-        program: lines
-            .nth(1)
-            .unwrap()
-            .strip_prefix("Program: ")
             .unwrap()
-            .split(',')
-            .map(|n| n.parse::<usize>().unwrap())
-            .collect(),
-        cur_op: 0,
-        result: vec![],
-    }
+    };
+    let (a, b, c) = (register("A"), register("B"), register("C"));
+    let program = lines
+        .nth(1)
+        .unwrap()
+        .strip_prefix("Program: ")
+        .unwrap()
+        .split(',')
+        .map(|n| n.parse().unwrap())
+        .collect();
+    Computer::new(a, b, c, program)
 }
 
 #[derive(Debug, Clone)]
 struct Computer {
-    reg_a: usize,
-    reg_b: usize,
-    reg_c: usize,
-    program: Vec<usize>,
-    cur_op: usize,
-    result: Vec<usize>,
+    machine: Machine<Op>,
+    /// The program as numbers, part 2 needs it to compare with the output
+    program: Vec<i64>,
 }
 
 impl Computer {
-    #[allow(dead_code)]
-    fn new(a: usize, b: usize, c: usize, program: Vec<usize>) -> Self {
-        Self {
-            reg_a: a,
-            reg_b: b,
-            reg_c: c,
-            program,
-            cur_op: 0,
-            result: vec![],
-        }
+    fn new(a: i64, b: i64, c: i64, program: Vec<i64>) -> Self {
+        let mut machine = Machine::new(decode(&program), 3);
+        machine.registers['A'] = a;
+        machine.registers['B'] = b;
+        machine.registers['C'] = c;
+        Self { machine, program }
     }
 
-    fn shortcut(start: &Self, reg_a: usize) -> Self {
-        let mut cur = start.clone();
-        cur.reg_a = reg_a;
-        cur.run_program();
-        cur
+    /// Runs the program and returns the machine after it halts.
+    fn run(&self) -> Machine<Op> {
+        let mut machine = self.machine.clone();
+        machine.run();
+        machine
     }
 
-    fn run_program(&mut self) {
-        while self.do_instruction() {}
+    /// Same as [`Computer::run`] but starts with a different value in register A.
+    fn run_with(&self, reg_a: i64) -> Machine<Op> {
+        let mut machine = self.machine.clone();
+        machine.registers['A'] = reg_a;
+        machine.run();
+        machine
     }
+}
 
-    fn do_instruction(&mut self) -> bool {
-        let op_literal = self.program[self.cur_op + 1];
-        let op_combo = self.get_combo_value();
-        let cur_literal = self.program[self.cur_op];
-        match Op::from(cur_literal) {
-            Op::Adv => {
-                self.reg_a /= 2_usize.pow(op_combo as u32);
-            }
-            Op::Bxl => {
-                self.reg_b ^= op_literal;
-            }
-            Op::Bst => {
-                self.reg_b = op_combo % 8;
-            }
-            Op::Jnz => {
-                if self.reg_a != 0 {
-                    self.cur_op = op_literal;
-                    return self.cur_op < self.program.len();
+/// Turns the numbers into instructions, every instruction takes 2 numbers.
+///
+/// An opcode without an operand at the end of the program is ignored because reading it halts
+/// the program anyway.
+fn decode(program: &[i64]) -> Vec<Op> {
+    program
+        .chunks_exact(2)
+        .map(|pair| {
+            let (opcode, operand) = (pair[0], pair[1]);
+            let combo = Combo::from(operand);
+            match opcode {
+                0 => Op::Adv(combo),
+                1 => Op::Bxl(operand),
+                2 => Op::Bst(combo),
+                3 => {
+                    assert!(
+                        operand % 2 == 0,
+                        "Jumping into the middle of an instruction isn't supported"
+                    );
+                    Op::Jnz(operand as usize / 2)
                 }
+                4 => Op::Bxc,
+                5 => Op::Out(combo),
+                6 => Op::Bdv(combo),
+                7 => Op::Cdv(combo),
+                _ => panic!("Invalid opcode {opcode}"),
             }
-            Op::Bxc => {
-                self.reg_b ^= self.reg_c;
-            }
-            Op::Out => {
-                self.result.push(op_combo % 8);
-            }
-            Op::Bdv => {
-                self.reg_b = self.reg_a / 2_usize.pow(op_combo as u32);
-            }
-            Op::Cdv => {
-                self.reg_c = self.reg_a / 2_usize.pow(op_combo as u32);
-            }
+        })
+        .collect()
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Combo {
+    Literal(i64),
+    Register(char),
+    Reserved,
+}
+
+impl From<i64> for Combo {
+    fn from(value: i64) -> Self {
+        match value {
+            0..=3 => Self::Literal(value),
+            4 => Self::Register('A'),
+            5 => Self::Register('B'),
+            6 => Self::Register('C'),
+            _ => Self::Reserved,
         }
-        self.cur_op += 2;
-        self.cur_op < self.program.len()
-    }
-
-    fn get_combo_value(&self) -> usize {
-        match self.program[self.cur_op + 1] {
-            v @ 0..=3 => v,
-            4 => self.reg_a,
-            5 => self.reg_b,
-            6 => self.reg_c,
-            7 => usize::MAX,
-            _ => unreachable!(),
+    }
+}
+
+impl std::fmt::Display for Combo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Combo::Literal(v) => write!(f, "{v}"),
+            Combo::Register(r) => write!(f, "{r}"),
+            Combo::Reserved => write!(f, "?"),
         }
     }
+}
 
-    fn get_result(&self) -> String {
-        self.result
-            .iter()
-            .map(|x| x.to_string())
-            .collect::<Vec<_>>()
-            .join(",")
+impl Combo {
+    fn value(&self, machine: &Machine<Op>) -> i64 {
+        match *self {
+            Combo::Literal(v) => v,
+            Combo::Register(r) => machine.registers[r],
+            Combo::Reserved => panic!("Combo operand 7 is reserved"),
+        }
     }
 }
 
+/// The jump target is the index of the instruction, not the number.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 enum Op {
-    Adv,
-    Bxl,
-    Bst,
-    Jnz,
+    Adv(Combo),
+    Bxl(i64),
+    Bst(Combo),
+    Jnz(usize),
     Bxc,
-    Out,
-    Bdv,
-    Cdv,
+    Out(Combo),
+    Bdv(Combo),
+    Cdv(Combo),
 }
 
-impl From<usize> for Op {
-    fn from(value: usize) -> Self {
-        match value {
-            0 => Self::Adv,
-            1 => Self::Bxl,
-            2 => Self::Bst,
-            3 => Self::Jnz,
-            4 => Self::Bxc,
-            5 => Self::Out,
-            6 => Self::Bdv,
-            7 => Self::Cdv,
-            _ => unreachable!(),
+impl Op {
+    fn operand(&self) -> Option<Combo> {
+        match *self {
+            Op::Adv(combo) | Op::Bst(combo) | Op::Out(combo) | Op::Bdv(combo) | Op::Cdv(combo) => {
+                Some(combo)
+            }
+            Op::Bxl(_) | Op::Jnz(_) | Op::Bxc => None,
+        }
+    }
+
+    /// The registers that the instruction uses.
+    fn reads(&self) -> Vec<char> {
+        let mut reads = match self {
+            Op::Adv(_) | Op::Bdv(_) | Op::Cdv(_) | Op::Jnz(_) => vec!['A'],
+            Op::Bxl(_) => vec!['B'],
+            Op::Bxc => vec!['B', 'C'],
+            Op::Bst(_) | Op::Out(_) => vec![],
+        };
+        if let Some(Combo::Register(r)) = self.operand() {
+            reads.push(r);
+        }
+        reads
+    }
+
+    /// The registers that the instruction changes.
+    fn writes(&self) -> Option<char> {
+        match self {
+            Op::Adv(_) => Some('A'),
+            Op::Bxl(_) | Op::Bst(_) | Op::Bxc | Op::Bdv(_) => Some('B'),
+            Op::Cdv(_) => Some('C'),
+            Op::Jnz(_) | Op::Out(_) => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Op {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Op::Adv(combo) => write!(f, "adv {combo}"),
+            Op::Bxl(literal) => write!(f, "bxl {literal}"),
+            Op::Bst(combo) => write!(f, "bst {combo}"),
+            Op::Jnz(target) => write!(f, "jnz {}", target * 2),
+            Op::Bxc => write!(f, "bxc"),
+            Op::Out(combo) => write!(f, "out {combo}"),
+            Op::Bdv(combo) => write!(f, "bdv {combo}"),
+            Op::Cdv(combo) => write!(f, "cdv {combo}"),
         }
     }
 }
 
+impl Instruction for Op {
+    fn execute(&self, machine: &mut Machine<Self>) -> Flow {
+        let divide = |machine: &Machine<Self>, combo: Combo| {
+            machine.registers['A']
+                .checked_shr(combo.value(machine) as u32)
+                .unwrap_or(0)
+        };
+        match *self {
+            Op::Adv(combo) => machine.registers['A'] = divide(machine, combo),
+            Op::Bxl(literal) => machine.registers['B'] ^= literal,
+            Op::Bst(combo) => machine.registers['B'] = combo.value(machine) % 8,
+            Op::Jnz(target) => {
+                if machine.registers['A'] != 0 {
+                    return Flow::Goto(target);
+                }
+            }
+            Op::Bxc => machine.registers['B'] ^= machine.registers['C'],
+            Op::Out(combo) => {
+                let value = combo.value(machine) % 8;
+                machine.output.push(value);
+            }
+            Op::Bdv(combo) => machine.registers['B'] = divide(machine, combo),
+            Op::Cdv(combo) => machine.registers['C'] = divide(machine, combo),
+        }
+        Flow::Next
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn part_1_test_1() {
-        let mut computer = Computer::new(0, 0, 9, vec![2, 6]);
-        computer.run_program();
-        assert_eq!(computer.get_result(), "");
-        assert_eq!(computer.reg_b, 1);
+        let machine = Computer::new(0, 0, 9, vec![2, 6]).run();
+        assert_eq!(get_result(&machine.output), "");
+        assert_eq!(machine.registers['B'], 1);
     }
 
     #[test]
@@ -213,24 +381,21 @@ mod tests {
 
     #[test]
     fn part_1_test_3() {
-        let mut computer = Computer::new(2024, 0, 0, vec![0, 1, 5, 4, 3, 0]);
-        computer.run_program();
-        assert_eq!(computer.get_result(), "4,2,5,6,7,7,7,7,3,1,0");
-        assert_eq!(computer.reg_a, 0);
+        let machine = Computer::new(2024, 0, 0, vec![0, 1, 5, 4, 3, 0]).run();
+        assert_eq!(get_result(&machine.output), "4,2,5,6,7,7,7,7,3,1,0");
+        assert_eq!(machine.registers['A'], 0);
     }
 
     #[test]
     fn part_1_test_4() {
-        let mut computer = Computer::new(0, 29, 0, vec![1, 7]);
-        computer.run_program();
-        assert_eq!(computer.reg_b, 26);
+        let machine = Computer::new(0, 29, 0, vec![1, 7]).run();
+        assert_eq!(machine.registers['B'], 26);
     }
 
     #[test]
     fn part_1_test_5() {
-        let mut computer = Computer::new(0, 2024, 43690, vec![4, 0]);
-        computer.run_program();
-        assert_eq!(computer.reg_b, 44354);
+        let machine = Computer::new(0, 2024, 43690, vec![4, 0]).run();
+        assert_eq!(machine.registers['B'], 44354);
     }
 
     #[test]
@@ -238,4 +403,64 @@ mod tests {
         let computer = load_input("example");
         assert_eq!(&part_1(&computer), "4,6,3,5,6,3,5,2,1,0");
     }
+
+    #[test]
+    fn part_2_test() {
+        let computer = Computer::new(2024, 0, 0, vec![0, 3, 5, 4, 3, 0]);
+        assert_eq!(part_2(&computer), Ok(117440));
+    }
+
+    #[test]
+    fn disassemble_test() {
+        let computer = Computer::new(0, 0, 0, vec![2, 4, 1, 3, 7, 5, 0, 3, 4, 1, 5, 5, 3, 0]);
+        assert_eq!(
+            disassemble(computer.machine.program()),
+            " 0: bst A\n 2: bxl 3\n 4: cdv B\n 6: adv 3\n 8: bxc\n10: out B\n12: jnz 0\n"
+        );
+    }
+
+    #[test]
+    fn analyse_test() {
+        let analyse_numbers = |program: Vec<i64>| {
+            let computer = Computer::new(0, 0, 0, program);
+            analyse(computer.machine.program())
+        };
+        assert_eq!(analyse_numbers(vec![0, 3, 5, 4, 3, 0]), Ok(3));
+        assert_eq!(
+            analyse_numbers(vec![2, 4, 1, 3, 7, 5, 0, 3, 4, 1, 5, 5, 3, 0]),
+            Ok(3)
+        );
+        assert_eq!(
+            analyse_numbers(vec![0, 1, 5, 4]),
+            Err(AnalysisError::NoLoop)
+        );
+        assert_eq!(analyse_numbers(vec![0, 1, 5, 4, 3, 0]), Ok(1));
+        assert_eq!(
+            analyse_numbers(vec![0, 3, 5, 4, 5, 4, 3, 0]),
+            Err(AnalysisError::Outputs(2))
+        );
+        assert_eq!(
+            analyse_numbers(vec![2, 4, 0, 5, 5, 4, 3, 0]),
+            Err(AnalysisError::BadShift(Combo::Register('B')))
+        );
+        assert_eq!(
+            analyse_numbers(vec![0, 3, 1, 1, 5, 5, 3, 0]),
+            Err(AnalysisError::ReadBeforeWrite(1, 'B'))
+        );
+        assert_eq!(
+            analyse_numbers(vec![3, 4, 0, 3, 5, 4, 3, 0]),
+            Err(AnalysisError::ExtraJump(0))
+        );
+        assert_eq!(
+            analyse_numbers(vec![5, 4, 3, 0]),
+            Err(AnalysisError::Shifts(0))
+        );
+    }
+
+    #[test]
+    fn no_solution() {
+        // B is set to 0 and then to 7 so it always outputs 7
+        let computer = Computer::new(0, 0, 0, vec![0, 3, 2, 0, 1, 7, 5, 5, 3, 0]);
+        assert_eq!(part_2(&computer), Err(AnalysisError::NoSolution));
+    }
 }
@@ -2,6 +2,8 @@ use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 
+use utils::graph::Graph;
+
 type ConnectionsMap = HashMap<String, HashSet<String>>;
 
 fn main() {
@@ -22,18 +24,13 @@ fn part_1(connections: &[(String, String)]) -> usize {
 }
 
 fn part_2(connections: &[(String, String)]) -> String {
-    let map = map_connections(connections);
-    let mut result = HashSet::new();
-    for name in map.keys() {
-        let mut cur = HashSet::new();
-        find_whole_group(&map, name, &mut cur);
-        if cur.len() > result.len() {
-            result = cur;
-        }
+    let mut graph = Graph::new();
+    for (first, second) in connections {
+        graph.add_edge(first.as_str(), second.as_str(), 1);
     }
-    let mut vec = Vec::from_iter(result);
-    vec.sort_unstable();
-    vec.join(",")
+    let mut clique: Vec<&str> = graph.max_clique().into_iter().copied().collect();
+    clique.sort_unstable();
+    clique.join(",")
 }
 
 fn find_group_of_3<'map>(map: &'map ConnectionsMap, start: &'map str) -> Vec<[&'map str; 3]> {
@@ -54,20 +51,6 @@ fn find_group_of_3<'map>(map: &'map ConnectionsMap, start: &'map str) -> Vec<[&'
     solutions
 }
 
-fn find_whole_group<'map>(map: &'map ConnectionsMap, cur: &'map str, seen: &mut HashSet<String>) {
-    seen.insert(cur.to_string());
-    for next in map.get(cur).unwrap() {
-        if seen.contains(next.as_str()) {
-            continue;
-        }
-        if seen.is_subset(map.get(next).unwrap()) {
-            find_whole_group(map, next, seen);
-        } else {
-            continue;
-        }
-    }
-}
-
 fn map_connections(connections: &[(String, String)]) -> ConnectionsMap {
     let mut map: ConnectionsMap = HashMap::new();
 
@@ -1,242 +1,253 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashSet;
 use std::fs::File;
 use std::hash::{Hash, Hasher};
 use std::io::BufReader;
 
+use utils::circuit::{self, read_number, Circuit, Operand};
 use utils::BufReadExt;
 
 fn main() {
-    let (wires, z_wires, mut gates) = load_input("input");
-    println!("Solution for part 1: {}", part_1(&wires, &z_wires));
+    let (circuit, mut gates) = load_input("input");
+    println!("Solution for part 1: {}", part_1(&circuit));
     println!("Solution for part 2: {}", part_2(&mut gates));
 }
 
-fn part_1(wires: &HashMap<String, Value>, z_wires: &[String]) -> u64 {
-    let mut cur_wires = wires.clone();
-    let mut visited = HashSet::new();
-    for z in z_wires {
-        solve(z, &mut cur_wires, &mut visited);
-    }
-    get_result(&cur_wires, 'z')
-}
-
-fn get_result(wires: &HashMap<String, Value>, prefix: char) -> u64 {
-    let mut solution = vec![];
-    for i in 0.. {
-        match wires.get(&format!("{}{i:02}", prefix)) {
-            Some(v) => solution.push(v.as_bool()),
-            None => break,
-        }
-    }
-    solution.reverse();
-    u64::from_str_radix(
-        &solution
-            .into_iter()
-            .map(|n| match n {
-                true => '1',
-                false => '0',
-            })
-            .collect::<String>(),
-        2,
-    )
-    .unwrap()
+fn part_1(circuit: &Circuit) -> u64 {
+    read_number(&circuit.evaluate().expect("Invalid circuit"), 'z')
 }
 
 fn part_2(gates: &mut HashSet<Gate>) -> String {
-    // println!("{:#?}", gates);
-    let mut solution = Vec::new();
-    solution.extend(swap_outputs(
-        Gate::new_empty(GateType::Xor, ["x11", "y11"]),
-        Gate::new_empty(GateType::And, ["x11", "y11"]),
-        gates,
-    ));
-    solution.extend(swap_outputs(
-        Gate::new_empty(GateType::Xor, ["rqq", "pvh"]),
-        Gate::new_empty(GateType::Or, ["pqv", "ffg"]),
-        gates,
-    ));
-
-    solution.extend(swap_outputs(
-        Gate::new_empty(GateType::Xor, ["kfq", "qgs"]),
-        Gate::new_empty(GateType::And, ["kfq", "qgs"]),
-        gates,
-    ));
-
-    solution.extend(swap_outputs(
-        Gate::new_empty(GateType::And, ["x39", "y39"]),
-        Gate::new_empty(GateType::Xor, ["sbq", "hkg"]),
-        gates,
-    ));
-    let mut c = String::from("wbd");
-    for i in 1..=44 {
-        let a = format!("x{:02}", i);
-        let b = format!("y{:02}", i);
-        c = full_adder(a, b, c, gates)
-    }
+    let bits = gates.iter().filter(|g| g.out.starts_with('z')).count() - 1;
+    let swaps = find_swaps(gates, bits);
+    assert!(
+        adds_correctly(gates, bits),
+        "Swapped {swaps:?} but the circuit still doesn't add correctly"
+    );
+    let mut solution: Vec<String> = swaps.into_iter().flatten().collect();
     solution.sort();
     solution.join(",")
 }
 
-fn swap_outputs(first: Gate, second: Gate, gates: &mut HashSet<Gate>) -> [String; 2] {
-    let mut one = gates.take(&first).unwrap();
-    let mut two = gates.take(&second).unwrap();
-    let tmp_one = one.out;
-    let tmp_two = two.out;
-    one.out = tmp_two.clone();
-    two.out = tmp_one.clone();
-    gates.insert(one);
-    gates.insert(two);
-    [tmp_one, tmp_two]
-}
-
-fn full_adder(a: String, b: String, c: String, gates: &HashSet<Gate>) -> String {
-    // println!("A: {a}, B: {b} C: {c}");
-    let mut a_b_sort = [a.clone(), b];
-    a_b_sort.sort();
-    let x = match gates.get(&Gate {
-        kind: GateType::Xor,
-        input: a_b_sort.clone(),
-        out: String::new(),
-    }) {
-        Some(g) => &g.out,
-        None => panic!("No A XOR B = X"),
-    };
-
-    // println!("X: {x}");
-
-    let y = match gates.get(&Gate {
-        kind: GateType::And,
-        input: a_b_sort,
-        out: String::new(),
-    }) {
-        Some(g) => &g.out,
-        None => panic!("No A AND B = Y"),
-    };
-
-    // println!("Y: {y}");
-
-    let mut x_c_sort = [x.to_string(), c.to_string()];
-    x_c_sort.sort();
-
-    let w = match gates.get(&Gate {
-        kind: GateType::And,
-        input: x_c_sort.clone(),
-        out: String::new(),
-    }) {
-        Some(g) => &g.out,
-        None => panic!("No X AND C = W"),
-    };
-
-    // println!("W: {w}");
-
-    match gates.get(&Gate {
-        kind: GateType::Xor,
-        input: x_c_sort,
-        out: String::new(),
-    }) {
-        Some(g) => {
-            if g.out != format!("z{}", &a[1..]) {
-                panic!("WRONG Z: {}", g.out)
+/// Walks the adder one bit at a time and fixes every bit that isn't wired correctly.
+///
+/// When a bit doesn't look like a full adder every pair of outputs near it gets swapped until it
+/// does. Pairs where the outputs break the gate type rules from
+/// [`suspicious_outputs()`][suspicious_outputs] are tried first.
+fn find_swaps(gates: &mut HashSet<Gate>, bits: usize) -> Vec<[String; 2]> {
+    let suspicious = suspicious_outputs(gates, bits);
+    let mut swaps = vec![];
+    let mut carry: Option<String> = None;
+    for bit in 0..bits {
+        let next = match check_bit(bit, carry.as_deref(), bits, gates) {
+            Some(next) => next,
+            None => {
+                let (swap, next) = repair_bit(bit, carry.as_deref(), bits, gates, &suspicious)
+                    .unwrap_or_else(|| panic!("No single swap fixes bit {bit}"));
+                swaps.push(swap);
+                next
             }
-        }
-        None => panic!("No X XOR C = Z"),
-    };
-
-    let mut w_y_sort = [w.to_string(), y.to_string()];
-    w_y_sort.sort();
-
-    match gates.get(&Gate {
-        kind: GateType::Or,
-        input: w_y_sort,
-        out: String::new(),
-    }) {
-        Some(g) => g.out.clone(),
-        None => panic!("No W OR Y = X"),
+        };
+        carry = Some(next);
     }
+    swaps
 }
 
-
-fn solve(value: &str, wires: &mut HashMap<String, Value>, visited: &mut HashSet<Gate>) -> bool {
-    match wires.get_mut(value).unwrap().clone() {
-        Value::Gate(gate) => {
-            let first = solve(&gate.input[0], wires, visited);
-            let second = solve(&gate.input[1], wires, visited);
-            let r = match gate.kind {
-                GateType::And => first && second,
-                GateType::Or => first | second,
-                GateType::Xor => first != second,
-            };
-            visited.insert(gate);
-            wires.insert(value.to_string(), Value::Value(r));
-            r
+/// Tries swapping every pair of outputs that are close to the bit and keeps the first one that
+/// makes it work.
+fn repair_bit(
+    bit: usize,
+    carry: Option<&str>,
+    bits: usize,
+    gates: &mut HashSet<Gate>,
+    suspicious: &HashSet<String>,
+) -> Option<([String; 2], String)> {
+    let mut close: HashSet<String> = [
+        format!("x{bit:02}"),
+        format!("y{bit:02}"),
+        carry.unwrap_or_default().to_string(),
+    ]
+    .into();
+    // Everything that is at most 2 gates away from the inputs
+    for _ in 0..2 {
+        let outputs: Vec<String> = gates
+            .iter()
+            .filter(|g| g.input.iter().any(|i| close.contains(i)))
+            .map(|g| g.out.clone())
+            .collect();
+        close.extend(outputs);
+    }
+    close.insert(format!("z{bit:02}"));
+    let mut candidates: Vec<&String> = close
+        .iter()
+        .filter(|c| gates.iter().any(|g| &g.out == *c))
+        .collect();
+    candidates.sort();
+
+    let mut pairs = vec![];
+    for (i, first) in candidates.iter().enumerate() {
+        for second in &candidates[i + 1..] {
+            pairs.push([first.to_string(), second.to_string()]);
+        }
+    }
+    pairs.sort_by_key(|pair| {
+        std::cmp::Reverse(pair.iter().filter(|w| suspicious.contains(*w)).count())
+    });
+
+    for [first, second] in pairs {
+        swap_outputs(&first, &second, gates);
+        if let Some(next) = check_bit(bit, carry, bits, gates) {
+            return Some(([first, second], next));
         }
-        Value::Value(v) => v,
+        swap_outputs(&first, &second, gates);
     }
+    None
 }
 
-fn load_input(name: &str) -> (HashMap<String, Value>, Vec<String>, HashSet<Gate>) {
-    let file = File::open(name).unwrap_or_else(|_| panic!("No \"{}\" file found", name));
-    let reader = BufReader::new(file);
-    let mut lines = reader.lines_unwrap();
-
-    let mut wires = HashMap::new();
-    let mut z_wires = vec![];
+/// Checks that a bit is wired correctly and returns the wire that has its carry.
+///
+/// Bit 0 is a half adder and the rest are full adders. The last carry has to go straight to the
+/// last z wire.
+fn check_bit(
+    bit: usize,
+    carry: Option<&str>,
+    bits: usize,
+    gates: &HashSet<Gate>,
+) -> Option<String> {
+    let next = match carry {
+        Some(c) => full_adder(bit, c, gates)?,
+        None => half_adder(gates)?,
+    };
+    let last = bit == bits - 1;
+    if (last && next != format!("z{bits:02}")) || (!last && next.starts_with('z')) {
+        return None;
+    }
+    Some(next)
+}
 
-    for l in &mut lines {
-        if l.is_empty() {
-            break;
-        }
-        let (name, value) = l.split_once(": ").unwrap();
-        if name.starts_with('z') {
-            z_wires.push(name.to_string());
+/// Gates that are wired in a way that can't be right in a ripple-carry adder.
+///
+/// - Every z output besides the last one comes from a XOR, the last one from an OR
+/// - A XOR that doesn't use x/y inputs has to output a z
+/// - A XOR of x/y (besides bit 0) has to go into another XOR
+/// - An AND (besides bit 0) has to go into an OR
+fn suspicious_outputs(gates: &HashSet<Gate>, bits: usize) -> HashSet<String> {
+    let last_z = format!("z{bits:02}");
+    let used_by = |wire: &str, kind: GateType| {
+        gates
+            .iter()
+            .any(|g| g.kind == kind && g.input.iter().any(|i| i == wire))
+    };
+    let mut suspicious = HashSet::new();
+    for gate in gates {
+        let from_input = gate.input[0].starts_with('x');
+        let first_bit = gate.input[0] == "x00";
+        let wrong = match gate.kind {
+            _ if gate.out == last_z => gate.kind != GateType::Or,
+            _ if gate.out.starts_with('z') => gate.kind != GateType::Xor,
+            GateType::Xor if !from_input => true,
+            GateType::Xor => !first_bit && !used_by(&gate.out, GateType::Xor),
+            GateType::And => !first_bit && !used_by(&gate.out, GateType::Or),
+            GateType::Or => false,
+        };
+        if wrong {
+            suspicious.insert(gate.out.clone());
         }
-        wires.insert(
-            name.to_string(),
-            Value::Value(match value {
-                "0" => false,
-                "1" => true,
-                _ => unreachable!(),
-            }),
-        );
     }
+    suspicious
+}
 
-    let mut gates = HashSet::new();
+/// Checks the circuit by adding some random numbers.
+fn adds_correctly(gates: &HashSet<Gate>, bits: usize) -> bool {
+    let mask = (1 << bits) - 1;
+    let mut seed: u64 = 0x2024_1224;
+    let mut random = || {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        seed & mask
+    };
 
-    for l in lines {
-        let gate = Gate::from(l);
-        if gate.out.starts_with('z') {
-            z_wires.push(gate.out.clone());
-        }
-        gates.insert(gate.clone());
-        wires.insert(gate.out.to_string(), Value::Gate(gate));
+    let mut tests = vec![(mask, mask), (mask, 1), (0, 0)];
+    tests.extend((0..100).map(|_| (random(), random())));
+    let mut circuit = Circuit::new(1);
+    for gate in gates {
+        circuit.add_gate(gate.out.clone(), gate.into());
     }
-    z_wires.sort_unstable();
-    (wires, z_wires, gates)
+    tests.into_iter().all(|(x, y)| {
+        for i in 0..bits {
+            circuit.set_wire(format!("x{i:02}"), x >> i & 1);
+            circuit.set_wire(format!("y{i:02}"), y >> i & 1);
+        }
+        // Swapped outputs can make loops
+        circuit
+            .evaluate()
+            .is_ok_and(|values| read_number(&values, 'z') == x + y)
+    })
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-enum Value {
-    Gate(Gate),
-    Value(bool),
+/// Swaps the outputs of the 2 gates that output the given wires.
+fn swap_outputs(first: &str, second: &str, gates: &mut HashSet<Gate>) {
+    let mut one = gates.iter().find(|g| g.out == first).unwrap().clone();
+    let mut two = gates.iter().find(|g| g.out == second).unwrap().clone();
+    gates.remove(&one);
+    gates.remove(&two);
+    std::mem::swap(&mut one.out, &mut two.out);
+    gates.insert(one);
+    gates.insert(two);
 }
 
-impl Value {
-    fn as_bool(&self) -> bool {
-        match self {
-            Value::Gate(_) => unreachable!(),
-            Value::Value(v) => *v,
-        }
+fn find_gate<'gates>(
+    kind: GateType,
+    first: &str,
+    second: &str,
+    gates: &'gates HashSet<Gate>,
+) -> Option<&'gates str> {
+    gates
+        .get(&Gate::new_empty(kind, [first, second]))
+        .map(|g| g.out.as_str())
+}
+
+/// Checks bit 0, it doesn't have a carry coming in.
+fn half_adder(gates: &HashSet<Gate>) -> Option<String> {
+    if find_gate(GateType::Xor, "x00", "y00", gates)? != "z00" {
+        return None;
     }
+    find_gate(GateType::And, "x00", "y00", gates).map(|c| c.to_string())
 }
 
-impl From<char> for Value {
-    fn from(value: char) -> Self {
-        Self::Value(match value {
-            '0' => false,
-            '1' => true,
-            _ => unreachable!(),
-        })
+/// Checks a single full adder and returns the carry for the next bit.
+///
+/// ```text
+/// A XOR B = X
+/// A AND B = Y
+/// X AND C = W
+/// X XOR C = Z
+/// W OR Y = carry
+/// ```
+fn full_adder(bit: usize, c: &str, gates: &HashSet<Gate>) -> Option<String> {
+    let a = format!("x{bit:02}");
+    let b = format!("y{bit:02}");
+    let x = find_gate(GateType::Xor, &a, &b, gates)?;
+    let y = find_gate(GateType::And, &a, &b, gates)?;
+    let w = find_gate(GateType::And, x, c, gates)?;
+    if find_gate(GateType::Xor, x, c, gates)? != format!("z{bit:02}") {
+        return None;
     }
+    find_gate(GateType::Or, w, y, gates).map(|c| c.to_string())
+}
+
+fn load_input(name: &str) -> (Circuit, HashSet<Gate>) {
+    let file = File::open(name).unwrap_or_else(|_| panic!("No \"{}\" file found", name));
+    let reader = BufReader::new(file);
+    let lines: Vec<String> = reader.lines_unwrap().collect();
+
+    let circuit = Circuit::parse(&lines, 1).expect("Invalid circuit");
+    let gates = lines
+        .into_iter()
+        .filter(|l| l.contains(" -> "))
+        .map(Gate::from)
+        .collect();
+    (circuit, gates)
 }
 
 #[derive(Debug, Clone)]
@@ -247,6 +258,7 @@ struct Gate {
 }
 
 impl Gate {
+    /// Gate that can be used to look up the real one, the output doesn't matter for that.
     fn new_empty(kind: GateType, input: [&str; 2]) -> Self {
         let mut input = [input[0].to_string(), input[1].to_string()];
         input.sort();
@@ -273,6 +285,17 @@ impl Hash for Gate {
     }
 }
 
+impl From<&Gate> for circuit::Gate {
+    fn from(value: &Gate) -> Self {
+        let [first, second] = value.input.clone().map(Operand::Wire);
+        match value.kind {
+            GateType::And => Self::And(first, second),
+            GateType::Or => Self::Or(first, second),
+            GateType::Xor => Self::Xor(first, second),
+        }
+    }
+}
+
 impl From<String> for Gate {
     fn from(value: String) -> Self {
         let mut split = value.split(' ');
@@ -311,13 +334,61 @@ mod tests {
 
     #[test]
     fn part_1_test_1() {
-        let (wires, z_wires, _) = load_input("example");
-        assert_eq!(part_1(&wires, &z_wires), 4);
+        let (circuit, _) = load_input("example");
+        assert_eq!(part_1(&circuit), 4);
     }
 
     #[test]
     fn part_1_test_2() {
-        let (wires, z_wires, _) = load_input("example2");
-        assert_eq!(part_1(&wires, &z_wires), 2024);
+        let (circuit, _) = load_input("example2");
+        assert_eq!(part_1(&circuit), 2024);
+    }
+
+    /// Builds a correct 6 bit adder.
+    fn adder() -> HashSet<Gate> {
+        let mut lines = vec![
+            "x00 XOR y00 -> z00".to_string(),
+            "x00 AND y00 -> c00".to_string(),
+        ];
+        for i in 1..6 {
+            let carry = if i == 5 {
+                "z06".to_string()
+            } else {
+                format!("c{i:02}")
+            };
+            lines.extend([
+                format!("x{i:02} XOR y{i:02} -> a{i:02}"),
+                format!("x{i:02} AND y{i:02} -> b{i:02}"),
+                format!("a{i:02} AND c{:02} -> w{i:02}", i - 1),
+                format!("c{:02} XOR a{i:02} -> z{i:02}", i - 1),
+                format!("w{i:02} OR b{i:02} -> {carry}"),
+            ]);
+        }
+        lines.into_iter().map(Gate::from).collect()
+    }
+
+    #[test]
+    fn correct_adder() {
+        let mut gates = adder();
+        assert!(adds_correctly(&gates, 6));
+        assert!(suspicious_outputs(&gates, 6).is_empty());
+        assert_eq!(part_2(&mut gates), "");
+    }
+
+    #[test]
+    fn part_2_test() {
+        let mut gates = adder();
+        swap_outputs("a02", "b02", &mut gates);
+        swap_outputs("z04", "c04", &mut gates);
+        swap_outputs("w05", "z05", &mut gates);
+        assert!(!adds_correctly(&gates, 6));
+        assert_eq!(
+            suspicious_outputs(&gates, 6),
+            ["b02", "a02", "c04", "z04", "w05", "z05"]
+                .map(String::from)
+                .into()
+        );
+        assert_eq!(part_2(&mut gates), "a02,b02,c04,w05,z04,z05");
+        assert!(adds_correctly(&gates, 6));
     }
 }
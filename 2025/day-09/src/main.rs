@@ -9,7 +9,7 @@ type Red = (usize, usize);
 fn main() {
     let input = load_input("input");
     println!("Solution for part 1: {}", part_1(&input));
-    // println!("Solution for part 2: {}", part_2(&input));
+    println!("Solution for part 2: {}", part_2(&input));
 }
 
 fn part_1(reds: &[Red]) -> usize {
@@ -33,6 +33,139 @@ fn part_1(reds: &[Red]) -> usize {
     biggest_area
 }
 
+fn part_2(reds: &[Red]) -> usize {
+    let floor = Floor::new(reds);
+    let mut biggest_area: usize = 0;
+
+    for (i, cur) in reds.iter().enumerate() {
+        for other in &reds[i + 1..] {
+            let x_diff = cur.0.abs_diff(other.0) + 1;
+            let y_diff = cur.1.abs_diff(other.1) + 1;
+            let area = x_diff * y_diff;
+            if area > biggest_area && floor.is_inside(*cur, *other) {
+                biggest_area = area;
+            }
+        }
+    }
+
+    biggest_area
+}
+
+/// One axis of the compressed floor.
+///
+/// Every coordinate that has a red tile gets its own column and the tiles between two of them get
+/// squashed into a single column, with an empty column on both ends.
+#[derive(Debug)]
+struct Axis {
+    values: Vec<usize>,
+    /// How many tiles every column stands for
+    widths: Vec<usize>,
+}
+
+impl Axis {
+    fn new(values: impl Iterator<Item = usize>) -> Self {
+        let mut values: Vec<usize> = values.collect();
+        values.sort_unstable();
+        values.dedup();
+        let mut widths = vec![1];
+        for (i, value) in values.iter().enumerate() {
+            widths.push(1);
+            match values.get(i + 1) {
+                Some(next) => widths.push(next - value - 1),
+                None => widths.push(1),
+            }
+        }
+        Self { values, widths }
+    }
+
+    fn index(&self, value: usize) -> usize {
+        self.values.binary_search(&value).unwrap() * 2 + 1
+    }
+
+    fn len(&self) -> usize {
+        self.widths.len()
+    }
+}
+
+/// The area covered by the red and green tiles with the coordinates compressed.
+#[derive(Debug)]
+struct Floor {
+    x_axis: Axis,
+    y_axis: Axis,
+    /// `outside[y][x]` is how many tiles in the compressed rectangle from `(0, 0)` up to but not
+    /// including `(x, y)` aren't red or green
+    outside: Vec<Vec<usize>>,
+}
+
+impl Floor {
+    fn new(reds: &[Red]) -> Self {
+        let x_axis = Axis::new(reds.iter().map(|r| r.0));
+        let y_axis = Axis::new(reds.iter().map(|r| r.1));
+
+        // Draws the loop of red and green tiles
+        let mut edge = vec![vec![false; x_axis.len()]; y_axis.len()];
+        for (i, cur) in reds.iter().enumerate() {
+            let next = reds[(i + 1) % reds.len()];
+            let (x_1, x_2) = (x_axis.index(cur.0), x_axis.index(next.0));
+            let (y_1, y_2) = (y_axis.index(cur.1), y_axis.index(next.1));
+            for row in &mut edge[y_1.min(y_2)..=y_1.max(y_2)] {
+                row[x_1.min(x_2)..=x_1.max(x_2)].fill(true);
+            }
+        }
+
+        // Everything that can be reached from the corner without crossing the loop is outside
+        let mut is_outside = vec![vec![false; x_axis.len()]; y_axis.len()];
+        is_outside[0][0] = true;
+        let mut stack: Vec<(usize, usize)> = vec![(0, 0)];
+        while let Some((x, y)) = stack.pop() {
+            for (n_x, n_y) in [
+                (x + 1, y),
+                (x, y + 1),
+                (x.wrapping_sub(1), y),
+                (x, y.wrapping_sub(1)),
+            ] {
+                if n_x < x_axis.len()
+                    && n_y < y_axis.len()
+                    && !edge[n_y][n_x]
+                    && !is_outside[n_y][n_x]
+                {
+                    is_outside[n_y][n_x] = true;
+                    stack.push((n_x, n_y));
+                }
+            }
+        }
+
+        let mut outside = vec![vec![0; x_axis.len() + 1]; y_axis.len() + 1];
+        for y in 0..y_axis.len() {
+            for x in 0..x_axis.len() {
+                let area = if is_outside[y][x] {
+                    x_axis.widths[x] * y_axis.widths[y]
+                } else {
+                    0
+                };
+                outside[y + 1][x + 1] =
+                    area + outside[y][x + 1] + outside[y + 1][x] - outside[y][x];
+            }
+        }
+
+        Self {
+            x_axis,
+            y_axis,
+            outside,
+        }
+    }
+
+    /// Checks if the rectangle with the 2 red tiles as corners only has red and green tiles.
+    fn is_inside(&self, first: Red, second: Red) -> bool {
+        let (x_1, x_2) = (self.x_axis.index(first.0), self.x_axis.index(second.0));
+        let (y_1, y_2) = (self.y_axis.index(first.1), self.y_axis.index(second.1));
+        let (x_1, x_2) = (x_1.min(x_2), x_1.max(x_2) + 1);
+        let (y_1, y_2) = (y_1.min(y_2), y_1.max(y_2) + 1);
+        self.outside[y_2][x_2] + self.outside[y_1][x_1]
+            == self.outside[y_1][x_2] + self.outside[y_2][x_1]
+    }
+}
+
 fn load_input(name: &str) -> Vec<Red> {
     let file = File::open(name).unwrap_or_else(|_| panic!("No \"{}\" file found", name));
     let reader = BufReader::new(file);
@@ -56,9 +189,21 @@ mod tests {
         assert_eq!(part_1(&input), 50);
     }
 
-    // #[test]
-    // fn part_2_test() {
-    //     let input = load_input("example");
-    //     assert_eq!(part_2(&input), 24);
-    // }
+    #[test]
+    fn part_2_test() {
+        let input = load_input("example");
+        assert_eq!(part_2(&input), 24);
+    }
+
+    #[test]
+    fn floor_test() {
+        let input = load_input("example");
+        let floor = Floor::new(&input);
+        assert!(floor.is_inside((9, 5), (2, 3)));
+        assert!(floor.is_inside((7, 3), (11, 1)));
+        assert!(floor.is_inside((9, 7), (9, 5)));
+        assert!(!floor.is_inside((7, 1), (11, 7)));
+        assert!(!floor.is_inside((2, 5), (11, 1)));
+        assert!(!floor.is_inside((2, 3), (7, 1)));
+    }
 }
@@ -0,0 +1,276 @@
+//! The assembunny language from 2016 days 12, 23 and 25 on top of [`vm`][crate::vm].
+//!
+//! Loops that only add or multiply registers get recognised when they are reached and are done in
+//! a single step. The program itself is never changed so this keeps working after `tgl`.
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::vm::{Flow, Instruction, Machine};
+
+/// A register or a number
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Value {
+    Register(char),
+    Literal(i64),
+}
+
+impl Value {
+    pub fn get(&self, machine: &Machine<Op>) -> i64 {
+        match *self {
+            Value::Register(r) => machine.registers[r],
+            Value::Literal(n) => n,
+        }
+    }
+}
+
+impl FromStr for Value {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "a" | "b" | "c" | "d" => Ok(Value::Register(s.chars().next().unwrap())),
+            _ => s.parse().map(Value::Literal).map_err(|_| ()),
+        }
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Register(r) => write!(f, "{r}"),
+            Value::Literal(n) => write!(f, "{n}"),
+        }
+    }
+}
+
+/// `tgl` can make instructions that don't make sense like `inc 1`, those get skipped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Op {
+    Cpy(Value, Value),
+    Inc(Value),
+    Dec(Value),
+    Jnz(Value, Value),
+    Tgl(Value),
+    Out(Value),
+}
+
+impl Op {
+    /// What `tgl` turns the instruction into.
+    pub fn toggled(&self) -> Self {
+        match *self {
+            Op::Inc(x) => Op::Dec(x),
+            Op::Dec(x) | Op::Tgl(x) | Op::Out(x) => Op::Inc(x),
+            Op::Jnz(x, y) => Op::Cpy(x, y),
+            Op::Cpy(x, y) => Op::Jnz(x, y),
+        }
+    }
+}
+
+impl Instruction for Op {
+    fn execute(&self, machine: &mut Machine<Self>) -> Flow {
+        if let Some(flow) = run_loop(machine) {
+            return flow;
+        }
+        match *self {
+            Op::Cpy(from, Value::Register(r)) => machine.registers[r] = from.get(machine),
+            Op::Inc(Value::Register(r)) => machine.registers[r] += 1,
+            Op::Dec(Value::Register(r)) => machine.registers[r] -= 1,
+            Op::Jnz(check, offset) => {
+                if check.get(machine) != 0 {
+                    return Flow::Jump(offset.get(machine) as isize);
+                }
+            }
+            Op::Tgl(offset) => {
+                let target = machine.pc() as i64 + offset.get(machine);
+                if let Some(op) = usize::try_from(target)
+                    .ok()
+                    .and_then(|t| machine.program_mut().get_mut(t))
+                {
+                    *op = op.toggled();
+                }
+            }
+            Op::Out(value) => machine.output.push(value.get(machine)),
+            Op::Cpy(_, Value::Literal(_))
+            | Op::Inc(Value::Literal(_))
+            | Op::Dec(Value::Literal(_)) => {}
+        }
+        Flow::Next
+    }
+}
+
+impl FromStr for Op {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut split = s.split_whitespace();
+        let name = split.next().ok_or(())?;
+        let args: Vec<Value> = split.map(|a| a.parse()).collect::<Result<_, _>>()?;
+        Ok(match (name, args.as_slice()) {
+            ("cpy", [x, y]) => Op::Cpy(*x, *y),
+            ("inc", [x]) => Op::Inc(*x),
+            ("dec", [x]) => Op::Dec(*x),
+            ("jnz", [x, y]) => Op::Jnz(*x, *y),
+            ("tgl", [x]) => Op::Tgl(*x),
+            ("out", [x]) => Op::Out(*x),
+            _ => return Err(()),
+        })
+    }
+}
+
+impl Display for Op {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Op::Cpy(x, y) => write!(f, "cpy {x} {y}"),
+            Op::Inc(x) => write!(f, "inc {x}"),
+            Op::Dec(x) => write!(f, "dec {x}"),
+            Op::Jnz(x, y) => write!(f, "jnz {x} {y}"),
+            Op::Tgl(x) => write!(f, "tgl {x}"),
+            Op::Out(x) => write!(f, "out {x}"),
+        }
+    }
+}
+
+/// Creates a machine with the 4 registers.
+pub fn machine(program: Vec<Op>) -> Machine<Op> {
+    Machine::new(program, 4)
+}
+
+/// `x += y` written as `inc x`, `dec y`, `jnz y -2` with the first 2 in any order.
+///
+/// Returns `(x, y)`.
+fn add_loop(ops: &[Op]) -> Option<(char, char)> {
+    use Value::{Literal, Register};
+    match *ops {
+        [Op::Inc(Register(x)), Op::Dec(Register(y)), Op::Jnz(Register(j), Literal(-2))]
+        | [Op::Dec(Register(y)), Op::Inc(Register(x)), Op::Jnz(Register(j), Literal(-2))]
+            if x != y && y == j =>
+        {
+            Some((x, y))
+        }
+        _ => None,
+    }
+}
+
+/// `a += b * d` written as `cpy b c`, an [`add_loop()`] of `a += c` and then `dec d`, `jnz d -5`.
+///
+/// Returns `(b, a, c, d)`.
+fn multiply_loop(ops: &[Op]) -> Option<(Value, char, char, char)> {
+    use Value::{Literal, Register};
+    let [Op::Cpy(from, Register(c)), ref inner @ .., Op::Dec(Register(d)), Op::Jnz(Register(j), Literal(-5))] =
+        *ops
+    else {
+        return None;
+    };
+    let (a, added) = add_loop(inner)?;
+    let distinct = added == c && d == j && d != a && d != c;
+    let from_ok = !matches!(from, Register(r) if r == a || r == c || r == d);
+    (distinct && from_ok).then_some((from, a, c, d))
+}
+
+/// Does the whole loop starting at the current instruction at once if there is one there.
+///
+/// Only loops that would end are done, anything else is left for the normal instructions.
+fn run_loop(machine: &mut Machine<Op>) -> Option<Flow> {
+    let rest = &machine.program()[machine.pc()..];
+    if let Some((from, a, c, d)) = rest.get(..6).and_then(multiply_loop) {
+        let (value, times) = (from.get(machine), machine.registers[d]);
+        if value > 0 && times > 0 {
+            machine.registers[a] += value * times;
+            machine.registers[c] = 0;
+            machine.registers[d] = 0;
+            return Some(Flow::Jump(6));
+        }
+    }
+    if let Some((x, y)) = rest.get(..3).and_then(add_loop) {
+        let value = machine.registers[y];
+        if value > 0 {
+            machine.registers[x] += value;
+            machine.registers[y] = 0;
+            return Some(Flow::Jump(3));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod assembunny_tests {
+    use super::*;
+
+    fn parse(program: &str) -> Vec<Op> {
+        program.lines().map(|l| l.trim().parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn parse_and_display() {
+        let program = "cpy 41 a\ninc a\ndec b\njnz c -2\ntgl d\nout 1";
+        let ops = parse(program);
+        assert_eq!(ops[0], Op::Cpy(Value::Literal(41), Value::Register('a')));
+        assert_eq!(ops[3], Op::Jnz(Value::Register('c'), Value::Literal(-2)));
+        let displayed: Vec<String> = ops.iter().map(|o| o.to_string()).collect();
+        assert_eq!(displayed.join("\n"), program);
+        for invalid in ["cpy 1", "inc e", "mul a b", ""] {
+            assert_eq!(invalid.parse::<Op>(), Err(()), "{invalid}");
+        }
+    }
+
+    /// 2016 day 12 example
+    #[test]
+    fn leonardos_monorail() {
+        let mut machine = machine(parse("cpy 41 a\ninc a\ninc a\ndec a\njnz a 2\ndec a"));
+        machine.run();
+        assert_eq!(machine.registers['a'], 42);
+    }
+
+    /// 2016 day 23 example
+    #[test]
+    fn toggle() {
+        let mut machine = machine(parse("cpy 2 a\ntgl a\ntgl a\ntgl a\ncpy 1 a\ndec a\ndec a"));
+        machine.run();
+        assert_eq!(machine.registers['a'], 3);
+        assert_eq!(machine.program()[3], Op::Inc(Value::Register('a')));
+
+        let toggled: Vec<Op> = parse("inc a\ndec a\ntgl a\nout a\njnz a 2\ncpy a b")
+            .iter()
+            .map(|o| o.toggled())
+            .collect();
+        assert_eq!(
+            toggled,
+            parse("dec a\ninc a\ninc a\ninc a\ncpy a 2\njnz a b")
+        );
+    }
+
+    #[test]
+    fn loops() {
+        // a = 1_000_000 + 1_000 * 2_000
+        let mut machine = machine(parse(
+            "cpy 1000000 b
+            inc a
+            dec b
+            jnz b -2
+            cpy 2000 d
+            cpy 1000 c
+            inc a
+            dec c
+            jnz c -2
+            dec d
+            jnz d -5",
+        ));
+        machine.run();
+        assert_eq!(machine.registers.as_slice(), [3_000_000, 0, 0, 0]);
+        assert!(machine.steps() < 10);
+
+        // Loops that don't fit the patterns still work
+        let mut machine = self::machine(parse("cpy 5 b\ndec a\ninc b\njnz b -2"));
+        machine.registers['a'] = 1;
+        assert_eq!(
+            machine.run_until(|m| m.registers['b'] > 10),
+            crate::vm::Stop::Condition
+        );
+        assert_eq!(add_loop(&parse("dec a\ninc a\njnz a -2")), None);
+        assert_eq!(
+            multiply_loop(&parse("cpy c c\ninc a\ndec c\njnz c -2\ndec d\njnz d -5")),
+            None
+        );
+    }
+}
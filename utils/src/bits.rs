@@ -0,0 +1,201 @@
+//! Reading and writing data that isn't aligned to bytes.
+//!
+//! Bits are read and written starting with the most significant bit of every byte, which is the
+//! same order as writing the bytes out in binary.
+
+/// Turns a hex string into bytes, `None` if it has something that isn't a hex digit.
+///
+/// An odd number of digits gets padded with a 0 at the end.
+pub fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    let digits: Vec<u8> = hex
+        .trim()
+        .chars()
+        .map(|c| c.to_digit(16).map(|d| d as u8))
+        .collect::<Option<_>>()?;
+    Some(
+        digits
+            .chunks(2)
+            .map(|pair| pair[0] << 4 | pair.get(1).copied().unwrap_or(0))
+            .collect(),
+    )
+}
+
+/// Turns bytes into an uppercase hex string.
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02X}")).collect()
+}
+
+#[derive(Debug, Clone)]
+pub struct BitReader<'a> {
+    bytes: &'a [u8],
+    /// Index of the next bit
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, position: 0 }
+    }
+
+    /// How many bits were read so far.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// How many bits are left.
+    pub fn remaining(&self) -> usize {
+        self.bytes.len() * 8 - self.position
+    }
+
+    pub fn read_bit(&mut self) -> bool {
+        assert!(self.remaining() > 0, "No bits left to read");
+        let byte = self.bytes[self.position / 8];
+        let bit = byte >> (7 - self.position % 8) & 1;
+        self.position += 1;
+        bit == 1
+    }
+
+    /// Reads `n` bits as a number, the first bit read is the most significant one.
+    ///
+    /// Panics if `n` is more than 64 or there aren't enough bits left.
+    pub fn read_bits(&mut self, n: u32) -> u64 {
+        assert!(n <= 64, "Can't read more than 64 bits at once");
+        assert!(
+            self.remaining() >= n as usize,
+            "Tried to read {n} bits but only {} are left",
+            self.remaining()
+        );
+        (0..n).fold(0, |value, _| value << 1 | self.read_bit() as u64)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BitWriter {
+    bytes: Vec<u8>,
+    /// How many bits were written
+    len: usize,
+}
+
+impl BitWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// How many bits were written.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn write_bit(&mut self, bit: bool) {
+        if self.len.is_multiple_of(8) {
+            self.bytes.push(0);
+        }
+        if bit {
+            *self.bytes.last_mut().unwrap() |= 1 << (7 - self.len % 8);
+        }
+        self.len += 1;
+    }
+
+    /// Writes the lowest `n` bits of the value, most significant one first.
+    pub fn write_bits(&mut self, value: u64, n: u32) {
+        assert!(n <= 64, "Can't write more than 64 bits at once");
+        for i in (0..n).rev() {
+            self.write_bit(value >> i & 1 == 1);
+        }
+    }
+
+    /// Writes all the bits from another writer.
+    pub fn append(&mut self, other: &BitWriter) {
+        let mut reader = BitReader::new(&other.bytes);
+        for _ in 0..other.len {
+            self.write_bit(reader.read_bit());
+        }
+    }
+
+    /// The written bytes, the last one is padded with 0s.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+#[cfg(test)]
+mod bits_tests {
+    use super::*;
+
+    #[test]
+    fn hex() {
+        assert_eq!(from_hex("D2FE28"), Some(vec![0xD2, 0xFE, 0x28]));
+        assert_eq!(from_hex("abc"), Some(vec![0xAB, 0xC0]));
+        assert_eq!(from_hex("XY"), None);
+        assert_eq!(to_hex(&[0xD2, 0xFE, 0x28, 0x01]), "D2FE2801");
+    }
+
+    #[test]
+    fn read() {
+        // 110100101111111000101000
+        let bytes = from_hex("D2FE28").unwrap();
+        let mut reader = BitReader::new(&bytes);
+        assert_eq!(reader.read_bits(3), 6);
+        assert_eq!(reader.read_bits(3), 4);
+        assert!(reader.read_bit());
+        assert_eq!(reader.read_bits(4), 0b0111);
+        assert_eq!(reader.position(), 11);
+        assert_eq!(reader.remaining(), 13);
+        assert_eq!(reader.read_bits(0), 0);
+        assert_eq!(reader.read_bits(13), 0b1111000101000);
+        assert_eq!(reader.remaining(), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn read_too_much() {
+        BitReader::new(&[0xFF]).read_bits(9);
+    }
+
+    #[test]
+    fn write() {
+        let mut writer = BitWriter::new();
+        writer.write_bits(6, 3);
+        writer.write_bits(4, 3);
+        writer.write_bit(true);
+        writer.write_bits(0b0111, 4);
+        writer.write_bits(0b1111000101, 10);
+        assert_eq!(writer.len(), 21);
+        assert_eq!(to_hex(writer.as_bytes()), "D2FE28");
+
+        let mut other = BitWriter::new();
+        other.write_bits(0b101, 3);
+        writer.append(&other);
+        assert_eq!(writer.len(), 24);
+        assert_eq!(writer.into_bytes(), [0xD2, 0xFE, 0x2D]);
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut writer = BitWriter::new();
+        let values: Vec<(u64, u32)> = (0..=64)
+            .map(|n| {
+                (
+                    0x5A5A_5A5A_5A5A_5A5A_u64.checked_shr(64 - n).unwrap_or(0),
+                    n,
+                )
+            })
+            .collect();
+        for (value, n) in &values {
+            writer.write_bits(*value, *n);
+        }
+        let bytes = writer.into_bytes();
+        let mut reader = BitReader::new(&bytes);
+        for (value, n) in values {
+            assert_eq!(reader.read_bits(n), value);
+        }
+    }
+}
@@ -0,0 +1,435 @@
+//! Circuits made out of wires and logic gates.
+//!
+//! Understands netlists that look like this:
+//! ```text
+//! x00: 1
+//! 123 -> y
+//! x AND y -> d
+//! NOT x -> h
+//! x LSHIFT 2 -> f
+//! x00 XOR y00 -> z00
+//! ```
+//! Every wire gets its value from exactly one gate. The values are kept in a `u64` and cut down to
+//! the width of the circuit after every gate.
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt::{Display, Write};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CircuitError {
+    /// The line couldn't be parsed.
+    Parse(String),
+    /// A gate uses a wire that nothing outputs to.
+    Undefined(String),
+    /// The wires that depend on each other in a loop (and everything that needs them).
+    Cycle(Vec<String>),
+}
+
+/// Input of a gate, either another wire or a number.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Operand {
+    Wire(String),
+    Const(u64),
+}
+
+impl From<&str> for Operand {
+    fn from(value: &str) -> Self {
+        match value.parse() {
+            Ok(n) => Self::Const(n),
+            Err(_) => Self::Wire(value.to_string()),
+        }
+    }
+}
+
+impl Display for Operand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Operand::Wire(w) => write!(f, "{w}"),
+            Operand::Const(n) => write!(f, "{n}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Gate {
+    And(Operand, Operand),
+    Or(Operand, Operand),
+    Xor(Operand, Operand),
+    Not(Operand),
+    LShift(Operand, u32),
+    RShift(Operand, u32),
+    /// Just passes the value through.
+    Copy(Operand),
+}
+
+impl Gate {
+    /// The operands that this gate reads.
+    pub fn inputs(&self) -> Vec<&Operand> {
+        match self {
+            Gate::And(a, b) | Gate::Or(a, b) | Gate::Xor(a, b) => vec![a, b],
+            Gate::Not(a) | Gate::LShift(a, _) | Gate::RShift(a, _) | Gate::Copy(a) => vec![a],
+        }
+    }
+
+    fn name(&self) -> String {
+        match self {
+            Gate::And(_, _) => "AND".to_string(),
+            Gate::Or(_, _) => "OR".to_string(),
+            Gate::Xor(_, _) => "XOR".to_string(),
+            Gate::Not(_) => "NOT".to_string(),
+            Gate::LShift(_, by) => format!("LSHIFT {by}"),
+            Gate::RShift(_, by) => format!("RSHIFT {by}"),
+            Gate::Copy(_) => String::new(),
+        }
+    }
+}
+
+impl FromStr for Gate {
+    type Err = CircuitError;
+
+    /// Parses the part before the `->`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || CircuitError::Parse(s.to_string());
+        let split: Vec<&str> = s.split_whitespace().collect();
+        let shift = |by: &str| by.parse::<u32>().map_err(|_| error());
+        Ok(match split[..] {
+            [value] => Self::Copy(value.into()),
+            ["NOT", value] => Self::Not(value.into()),
+            [first, "AND", second] => Self::And(first.into(), second.into()),
+            [first, "OR", second] => Self::Or(first.into(), second.into()),
+            [first, "XOR", second] => Self::Xor(first.into(), second.into()),
+            [value, "LSHIFT", by] => Self::LShift(value.into(), shift(by)?),
+            [value, "RSHIFT", by] => Self::RShift(value.into(), shift(by)?),
+            _ => return Err(error()),
+        })
+    }
+}
+
+impl Display for Gate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Gate::And(a, b) | Gate::Or(a, b) | Gate::Xor(a, b) => {
+                write!(f, "{a} {} {b}", self.name())
+            }
+            Gate::Not(a) => write!(f, "NOT {a}"),
+            Gate::LShift(a, _) | Gate::RShift(a, _) => write!(f, "{a} {}", self.name()),
+            Gate::Copy(a) => write!(f, "{a}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Circuit {
+    /// Output wire -> gate
+    gates: HashMap<String, Gate>,
+    width: u32,
+}
+
+impl Circuit {
+    /// Creates an empty circuit where every value is `width` bits wide.
+    pub fn new(width: u32) -> Self {
+        assert!(
+            (1..=64).contains(&width),
+            "Width has to be between 1 and 64"
+        );
+        Self {
+            gates: HashMap::new(),
+            width,
+        }
+    }
+
+    /// Parses a netlist, empty lines are skipped.
+    ///
+    /// Lines can either be `<gate> -> <wire>` or `<wire>: <number>`.
+    pub fn parse<I, S>(lines: I, width: u32) -> Result<Self, CircuitError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut circuit = Self::new(width);
+        for line in lines {
+            let line = line.as_ref().trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some((gate, wire)) = line.split_once(" -> ") {
+                circuit.add_gate(wire.trim(), gate.parse()?);
+            } else if let Some((wire, value)) = line.split_once(": ") {
+                let value = value
+                    .trim()
+                    .parse()
+                    .map_err(|_| CircuitError::Parse(line.to_string()))?;
+                circuit.set_wire(wire.trim(), value);
+            } else {
+                return Err(CircuitError::Parse(line.to_string()));
+            }
+        }
+        Ok(circuit)
+    }
+
+    /// Connects the gate to the wire, replaces whatever was connected to it before.
+    pub fn add_gate(&mut self, wire: impl Into<String>, gate: Gate) {
+        self.gates.insert(wire.into(), gate);
+    }
+
+    /// Sets the wire to a fixed value, replaces whatever was connected to it before.
+    pub fn set_wire(&mut self, wire: impl Into<String>, value: u64) {
+        self.add_gate(wire, Gate::Copy(Operand::Const(value)));
+    }
+
+    pub fn gates(&self) -> &HashMap<String, Gate> {
+        &self.gates
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    fn mask(&self) -> u64 {
+        u64::MAX >> (64 - self.width)
+    }
+
+    /// Calculates the value of every wire.
+    ///
+    /// Goes through the wires in topological order so every wire is only calculated once.
+    pub fn evaluate(&self) -> Result<HashMap<String, u64>, CircuitError> {
+        // How many wires every wire is still waiting for and which wires are waiting for it
+        let mut waiting_for: HashMap<&str, usize> = HashMap::new();
+        let mut needed_by: HashMap<&str, Vec<&str>> = HashMap::new();
+        for (wire, gate) in &self.gates {
+            let mut count = 0;
+            for input in gate.inputs() {
+                if let Operand::Wire(w) = input {
+                    if !self.gates.contains_key(w) {
+                        return Err(CircuitError::Undefined(w.clone()));
+                    }
+                    needed_by.entry(w).or_default().push(wire);
+                    count += 1;
+                }
+            }
+            waiting_for.insert(wire, count);
+        }
+
+        let mut queue: VecDeque<&str> = waiting_for
+            .iter()
+            .filter(|(_, count)| **count == 0)
+            .map(|(wire, _)| *wire)
+            .collect();
+        let mut values: HashMap<String, u64> = HashMap::with_capacity(self.gates.len());
+        while let Some(wire) = queue.pop_front() {
+            let value = self.calculate(&self.gates[wire], &values);
+            values.insert(wire.to_string(), value);
+            for next in needed_by.get(wire).into_iter().flatten() {
+                let count = waiting_for.get_mut(next).unwrap();
+                *count -= 1;
+                if *count == 0 {
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        if values.len() != self.gates.len() {
+            let mut stuck: Vec<String> = self
+                .gates
+                .keys()
+                .filter(|w| !values.contains_key(*w))
+                .cloned()
+                .collect();
+            stuck.sort_unstable();
+            return Err(CircuitError::Cycle(stuck));
+        }
+        Ok(values)
+    }
+
+    /// Calculates the value of a single wire.
+    pub fn value(&self, wire: &str) -> Result<u64, CircuitError> {
+        self.evaluate()?
+            .remove(wire)
+            .ok_or_else(|| CircuitError::Undefined(wire.to_string()))
+    }
+
+    fn calculate(&self, gate: &Gate, values: &HashMap<String, u64>) -> u64 {
+        let get = |operand: &Operand| match operand {
+            Operand::Wire(w) => values[w],
+            Operand::Const(n) => *n,
+        };
+        let value = match gate {
+            Gate::And(a, b) => get(a) & get(b),
+            Gate::Or(a, b) => get(a) | get(b),
+            Gate::Xor(a, b) => get(a) ^ get(b),
+            Gate::Not(a) => !get(a),
+            Gate::LShift(a, by) => get(a).checked_shl(*by).unwrap_or(0),
+            Gate::RShift(a, by) => get(a).checked_shr(*by).unwrap_or(0),
+            Gate::Copy(a) => get(a),
+        };
+        value & self.mask()
+    }
+
+    /// Exports the circuit in the Graphviz DOT format.
+    ///
+    /// Every wire is a node labeled with the gate that sets it, numbers get their own nodes.
+    pub fn to_dot(&self) -> String {
+        let mut wires: Vec<&String> = self.gates.keys().collect();
+        wires.sort_unstable();
+
+        let mut dot = String::from("digraph circuit {\n");
+        for wire in wires {
+            let gate = &self.gates[wire];
+            let label = match gate {
+                Gate::Copy(Operand::Const(n)) => format!("{wire} = {n}"),
+                Gate::Copy(_) => wire.to_string(),
+                _ => format!("{wire}\\n{}", gate.name()),
+            };
+            writeln!(dot, "    \"{wire}\" [label=\"{label}\"];").unwrap();
+            for input in gate.inputs() {
+                match input {
+                    Operand::Wire(w) => writeln!(dot, "    \"{w}\" -> \"{wire}\";").unwrap(),
+                    Operand::Const(n) if !matches!(gate, Gate::Copy(_)) => {
+                        writeln!(dot, "    \"{wire}_{n}\" [label=\"{n}\", shape=box];").unwrap();
+                        writeln!(dot, "    \"{wire}_{n}\" -> \"{wire}\";").unwrap();
+                    }
+                    Operand::Const(_) => (),
+                }
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// Puts together the number from the 1 bit wires that start with `prefix`.
+///
+/// `z00` is the lowest bit, `z01` the next one and so on.
+pub fn read_number(values: &HashMap<String, u64>, prefix: char) -> u64 {
+    let mut number = 0;
+    for (wire, value) in values {
+        if let Some(bit) = wire
+            .strip_prefix(prefix)
+            .and_then(|b| b.parse::<u32>().ok())
+        {
+            number |= (value & 1) << bit;
+        }
+    }
+    number
+}
+
+#[cfg(test)]
+mod circuit_tests {
+    use super::*;
+
+    /// 2015 day 7 example
+    const EXAMPLE: [&str; 8] = [
+        "123 -> x",
+        "456 -> y",
+        "x AND y -> d",
+        "x OR y -> e",
+        "x LSHIFT 2 -> f",
+        "y RSHIFT 2 -> g",
+        "NOT x -> h",
+        "NOT y -> i",
+    ];
+
+    #[test]
+    fn evaluate() {
+        let circuit = Circuit::parse(EXAMPLE, 16).unwrap();
+        let values = circuit.evaluate().unwrap();
+        for (wire, value) in [
+            ("d", 72),
+            ("e", 507),
+            ("f", 492),
+            ("g", 114),
+            ("h", 65412),
+            ("i", 65079),
+            ("x", 123),
+            ("y", 456),
+        ] {
+            assert_eq!(values[wire], value, "{wire}");
+        }
+        assert_eq!(circuit.value("h"), Ok(65412));
+    }
+
+    #[test]
+    fn width() {
+        let circuit = Circuit::parse(EXAMPLE, 8).unwrap();
+        assert_eq!(circuit.value("h"), Ok(!123 & 0xFF));
+        assert_eq!(circuit.value("f"), Ok((123 << 2) & 0xFF));
+    }
+
+    #[test]
+    fn override_wire() {
+        let mut circuit = Circuit::parse(EXAMPLE, 16).unwrap();
+        circuit.set_wire("x", 1);
+        assert_eq!(circuit.value("d"), Ok(0));
+        assert_eq!(circuit.value("e"), Ok(457));
+    }
+
+    #[test]
+    fn bits() {
+        let lines = [
+            "x00: 1",
+            "x01: 0",
+            "y00: 1",
+            "y01: 1",
+            "",
+            "x00 XOR y00 -> z00",
+            "x00 AND y00 -> c00",
+            "x01 XOR y01 -> a01",
+            "x01 AND y01 -> b01",
+            "a01 XOR c00 -> z01",
+            "a01 AND c00 -> w01",
+            "w01 OR b01 -> z02",
+        ];
+        let circuit = Circuit::parse(lines, 1).unwrap();
+        let values = circuit.evaluate().unwrap();
+        assert_eq!(read_number(&values, 'x'), 1);
+        assert_eq!(read_number(&values, 'y'), 3);
+        assert_eq!(read_number(&values, 'z'), 4);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            Circuit::parse(["x FOO y -> z"], 16),
+            Err(CircuitError::Parse("x FOO y".to_string()))
+        );
+        assert_eq!(
+            Circuit::parse(["x LSHIFT y -> z"], 16),
+            Err(CircuitError::Parse("x LSHIFT y".to_string()))
+        );
+        let circuit = Circuit::parse(["x AND y -> z", "1 -> x"], 16).unwrap();
+        assert_eq!(
+            circuit.evaluate(),
+            Err(CircuitError::Undefined("y".to_string()))
+        );
+        let circuit = Circuit::parse(["a -> b", "b OR 1 -> a", "a -> c", "1 -> d"], 16).unwrap();
+        assert_eq!(
+            circuit.evaluate(),
+            Err(CircuitError::Cycle(
+                ["a", "b", "c"].map(String::from).to_vec()
+            ))
+        );
+    }
+
+    #[test]
+    fn display_gate() {
+        for gate in ["x AND y", "NOT x", "x LSHIFT 2", "123", "a XOR 3"] {
+            assert_eq!(gate.parse::<Gate>().unwrap().to_string(), gate);
+        }
+    }
+
+    #[test]
+    fn dot() {
+        let circuit = Circuit::parse(["1 -> x", "x AND 3 -> y"], 16).unwrap();
+        assert_eq!(
+            circuit.to_dot(),
+            "digraph circuit {\n    \
+                \"x\" [label=\"x = 1\"];\n    \
+                \"y\" [label=\"y\\nAND\"];\n    \
+                \"x\" -> \"y\";\n    \
+                \"y_3\" [label=\"3\", shape=box];\n    \
+                \"y_3\" -> \"y\";\n\
+            }\n"
+        );
+    }
+}
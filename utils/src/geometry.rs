@@ -0,0 +1,250 @@
+use crate::math::gcd;
+
+/// (x, y)
+pub type Point = (isize, isize);
+
+/// Where a point lies in relation to a polygon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Location {
+    Inside,
+    Boundary,
+    Outside,
+}
+
+/// Twice the area of a polygon using the shoelace formula.
+///
+/// The polygon is given by its vertices in order (either direction) and is implicitly closed, the
+/// first point doesn't need to be repeated at the end. Twice the area is always an integer for
+/// lattice points so this is exact.
+pub fn double_area(polygon: &[Point]) -> isize {
+    let mut sum = 0;
+    for (i, cur) in polygon.iter().enumerate() {
+        let next = polygon[(i + 1) % polygon.len()];
+        sum += cur.0 * next.1 - next.0 * cur.1;
+    }
+    sum.abs()
+}
+
+/// Area of a polygon using the shoelace formula.
+///
+/// Rounds down if the area isn't a whole number, use [`double_area()`][double_area] if that
+/// matters.
+pub fn area(polygon: &[Point]) -> isize {
+    double_area(polygon) / 2
+}
+
+/// Number of lattice points on the edges of a polygon.
+pub fn boundary_points(polygon: &[Point]) -> isize {
+    let mut count = 0;
+    for (i, cur) in polygon.iter().enumerate() {
+        let next = polygon[(i + 1) % polygon.len()];
+        let x_diff = cur.0.abs_diff(next.0);
+        let y_diff = cur.1.abs_diff(next.1);
+        count += match (x_diff, y_diff) {
+            (0, diff) | (diff, 0) => diff,
+            _ => gcd(x_diff, y_diff),
+        };
+    }
+    count as isize
+}
+
+/// Number of lattice points strictly inside a polygon.
+///
+/// Uses Pick's theorem: `A = i + b/2 - 1` so `i = A - b/2 + 1`.
+pub fn interior_points(polygon: &[Point]) -> isize {
+    (double_area(polygon) - boundary_points(polygon) + 2) / 2
+}
+
+/// Number of lattice points inside or on the edges of a polygon.
+///
+/// For a path that goes through the middle of grid tiles (2023 day 18, 2023 day 10) this is the
+/// amount of tiles covered by the path and everything it encloses.
+pub fn covered_points(polygon: &[Point]) -> isize {
+    interior_points(polygon) + boundary_points(polygon)
+}
+
+/// Checks if `point` is on the segment from `start` to `end`.
+pub fn on_segment(point: Point, start: Point, end: Point) -> bool {
+    let cross = (end.0 - start.0) * (point.1 - start.1) - (point.0 - start.0) * (end.1 - start.1);
+    cross == 0
+        && point.0 >= start.0.min(end.0)
+        && point.0 <= start.0.max(end.0)
+        && point.1 >= start.1.min(end.1)
+        && point.1 <= start.1.max(end.1)
+}
+
+/// Finds out if a point is inside, outside or on the edge of a simple polygon.
+///
+/// Works for any simple polygon, not only rectilinear ones. Uses ray casting towards positive x.
+pub fn locate_point(point: Point, polygon: &[Point]) -> Location {
+    let mut inside = false;
+    for (i, &start) in polygon.iter().enumerate() {
+        let end = polygon[(i + 1) % polygon.len()];
+        if on_segment(point, start, end) {
+            return Location::Boundary;
+        }
+        // Half-open check so that a vertex on the ray is only counted once
+        if (start.1 > point.1) != (end.1 > point.1) {
+            let cross =
+                (end.0 - start.0) * (point.1 - start.1) - (point.0 - start.0) * (end.1 - start.1);
+            // Edge crosses the ray to the right of the point
+            if (cross > 0) == (end.1 > start.1) {
+                inside = !inside;
+            }
+        }
+    }
+    if inside {
+        Location::Inside
+    } else {
+        Location::Outside
+    }
+}
+
+/// Checks if a point is inside a polygon or on its edge.
+pub fn point_in_polygon(point: Point, polygon: &[Point]) -> bool {
+    locate_point(point, polygon) != Location::Outside
+}
+
+/// Checks if the whole rectangle with the 2 given opposite corners is inside a rectilinear polygon.
+///
+/// The edges of the rectangle are allowed to touch the edges of the polygon. Only the vertex
+/// coordinates of the polygon can change if a point is inside or not so it's enough to check one
+/// point for every "cell" formed by splitting the rectangle at those coordinates. All coordinates
+/// get doubled so the middle points of those cells are still on the lattice.
+pub fn rectangle_in_polygon(corner_a: Point, corner_b: Point, polygon: &[Point]) -> bool {
+    let min_x = corner_a.0.min(corner_b.0);
+    let max_x = corner_a.0.max(corner_b.0);
+    let min_y = corner_a.1.min(corner_b.1);
+    let max_y = corner_a.1.max(corner_b.1);
+
+    let xs = split_points(min_x, max_x, polygon.iter().map(|p| p.0));
+    let ys = split_points(min_y, max_y, polygon.iter().map(|p| p.1));
+    let doubled: Vec<Point> = polygon.iter().map(|p| (p.0 * 2, p.1 * 2)).collect();
+
+    for &y in &ys {
+        for &x in &xs {
+            if !point_in_polygon((x, y), &doubled) {
+                return false;
+            }
+        }
+    }
+    true
+}
+
+/// The (doubled) coordinates that need to be checked on one axis.
+///
+/// Every coordinate in the range where the polygon has a vertex plus the middle points between
+/// them.
+fn split_points(min: isize, max: isize, coords: impl Iterator<Item = isize>) -> Vec<isize> {
+    let mut split: Vec<isize> = coords.filter(|c| *c > min && *c < max).collect();
+    split.push(min);
+    split.push(max);
+    split.sort_unstable();
+    split.dedup();
+
+    let mut points = vec![split[0] * 2];
+    for double in split.windows(2) {
+        points.push(double[0] + double[1]);
+        points.push(double[1] * 2);
+    }
+    points.dedup();
+    points
+}
+
+#[cfg(test)]
+mod geometry_tests {
+    use super::*;
+
+    /// 2023 day 18 example
+    const LAGOON: [Point; 14] = [
+        (0, 0),
+        (6, 0),
+        (6, -5),
+        (4, -5),
+        (4, -7),
+        (6, -7),
+        (6, -9),
+        (1, -9),
+        (1, -7),
+        (0, -7),
+        (0, -5),
+        (2, -5),
+        (2, -2),
+        (0, -2),
+    ];
+
+    /// 2025 day 9 example
+    const RED: [Point; 8] = [
+        (7, 1),
+        (11, 1),
+        (11, 7),
+        (9, 7),
+        (9, 5),
+        (2, 5),
+        (2, 3),
+        (7, 3),
+    ];
+
+    #[test]
+    fn square_area() {
+        let square = [(0, 0), (4, 0), (4, 4), (0, 4)];
+        assert_eq!(double_area(&square), 32);
+        assert_eq!(area(&square), 16);
+        assert_eq!(boundary_points(&square), 16);
+        assert_eq!(interior_points(&square), 9);
+        assert_eq!(covered_points(&square), 25);
+    }
+
+    #[test]
+    fn direction_doesnt_matter() {
+        let mut reversed = LAGOON;
+        reversed.reverse();
+        assert_eq!(double_area(&LAGOON), double_area(&reversed));
+    }
+
+    #[test]
+    fn triangle() {
+        let triangle = [(0, 0), (4, 0), (0, 3)];
+        assert_eq!(double_area(&triangle), 12);
+        // 4 + 3 + gcd(4, 3)
+        assert_eq!(boundary_points(&triangle), 8);
+        assert_eq!(interior_points(&triangle), 3);
+    }
+
+    #[test]
+    fn lagoon() {
+        assert_eq!(boundary_points(&LAGOON), 38);
+        assert_eq!(covered_points(&LAGOON), 62);
+    }
+
+    #[test]
+    fn locate() {
+        assert_eq!(locate_point((5, -1), &LAGOON), Location::Inside);
+        assert_eq!(locate_point((0, 0), &LAGOON), Location::Boundary);
+        assert_eq!(locate_point((3, 0), &LAGOON), Location::Boundary);
+        assert_eq!(locate_point((1, -3), &LAGOON), Location::Outside);
+        assert_eq!(locate_point((5, -6), &LAGOON), Location::Outside);
+        assert_eq!(locate_point((-1, 0), &LAGOON), Location::Outside);
+        assert_eq!(locate_point((7, -5), &LAGOON), Location::Outside);
+        assert!(point_in_polygon((3, -8), &LAGOON));
+    }
+
+    #[test]
+    fn locate_non_rectilinear() {
+        let triangle = [(0, 0), (4, 0), (0, 4)];
+        assert_eq!(locate_point((1, 1), &triangle), Location::Inside);
+        assert_eq!(locate_point((2, 2), &triangle), Location::Boundary);
+        assert_eq!(locate_point((3, 3), &triangle), Location::Outside);
+    }
+
+    #[test]
+    fn rectangles() {
+        assert!(rectangle_in_polygon((7, 3), (11, 1), &RED));
+        assert!(rectangle_in_polygon((9, 7), (9, 5), &RED));
+        assert!(rectangle_in_polygon((9, 5), (2, 3), &RED));
+        assert!(!rectangle_in_polygon((2, 5), (11, 1), &RED));
+        assert!(!rectangle_in_polygon((2, 3), (11, 7), &RED));
+        // Corners are inside but the middle goes outside
+        assert!(!rectangle_in_polygon((0, 0), (6, -9), &LAGOON));
+    }
+}
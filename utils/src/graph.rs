@@ -0,0 +1,498 @@
+//! Weighted graph stored as adjacency lists and some algorithms that keep showing up.
+//!
+//! Nodes are stored in a `Vec` and most methods work with the index of a node, use
+//! [`Graph::index_of()`] and [`Graph::node()`] to go between the two.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::hash::Hash;
+
+use crate::cursor::modifiers::CARDINAL_MODIF;
+use crate::cursor::Direction;
+
+#[derive(Debug, Clone)]
+pub struct Graph<N> {
+    nodes: Vec<N>,
+    indices: HashMap<N, usize>,
+    /// (target, weight)
+    edges: Vec<Vec<(usize, usize)>>,
+}
+
+impl<N> Graph<N>
+where
+    N: Hash + Eq + Clone,
+{
+    pub fn new() -> Self {
+        Self {
+            nodes: vec![],
+            indices: HashMap::new(),
+            edges: vec![],
+        }
+    }
+
+    /// Adds the node if it doesn't exist yet and returns its index.
+    pub fn add_node(&mut self, node: N) -> usize {
+        if let Some(&i) = self.indices.get(&node) {
+            return i;
+        }
+        let i = self.nodes.len();
+        self.nodes.push(node.clone());
+        self.indices.insert(node, i);
+        self.edges.push(vec![]);
+        i
+    }
+
+    /// Adds an edge going both ways, adds the nodes if needed.
+    pub fn add_edge(&mut self, first: N, second: N, weight: usize) {
+        let first = self.add_node(first);
+        let second = self.add_node(second);
+        self.edges[first].push((second, weight));
+        self.edges[second].push((first, weight));
+    }
+
+    /// Adds an edge that only goes from `from` to `to`, adds the nodes if needed.
+    pub fn add_directed_edge(&mut self, from: N, to: N, weight: usize) {
+        let from = self.add_node(from);
+        let to = self.add_node(to);
+        self.edges[from].push((to, weight));
+    }
+
+    pub fn index_of(&self, node: &N) -> Option<usize> {
+        self.indices.get(node).copied()
+    }
+
+    pub fn node(&self, index: usize) -> &N {
+        &self.nodes[index]
+    }
+
+    pub fn nodes(&self) -> &[N] {
+        &self.nodes
+    }
+
+    /// The (target, weight) of every edge going out of the node.
+    pub fn neighbours(&self, index: usize) -> &[(usize, usize)] {
+        &self.edges[index]
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Finds the biggest group of nodes that are all connected to each other.
+    ///
+    /// Uses Bron–Kerbosch with pivoting, the edges are treated as undirected.
+    pub fn max_clique(&self) -> Vec<&N> {
+        let neighbours = self.undirected_neighbours();
+        let mut best = vec![];
+        bron_kerbosch(
+            &neighbours,
+            &mut vec![],
+            (0..self.len()).collect(),
+            HashSet::new(),
+            &mut best,
+        );
+        best.into_iter().map(|i| &self.nodes[i]).collect()
+    }
+
+    /// Finds the smallest total weight of edges that need to be removed to split the graph in 2.
+    ///
+    /// Returns the weight of the cut and the nodes on one side of it. Uses Stoer–Wagner, the edges
+    /// are treated as undirected. Panics if the graph has less than 2 nodes.
+    pub fn min_cut(&self) -> (usize, Vec<&N>) {
+        assert!(self.len() >= 2, "Graph needs at least 2 nodes to be cut");
+        // Merged nodes, the weights of parallel edges get added together
+        let mut adjacency: Vec<HashMap<usize, usize>> = vec![HashMap::new(); self.len()];
+        for (from, edges) in self.edges.iter().enumerate() {
+            for &(to, weight) in edges {
+                if from != to {
+                    *adjacency[from].entry(to).or_default() += weight;
+                }
+            }
+        }
+        // Directed edges only get added one way so make sure they are the same both ways
+        for from in 0..self.len() {
+            for (to, weight) in adjacency[from].clone() {
+                let other = adjacency[to].entry(from).or_default();
+                *other = (*other).max(weight);
+            }
+        }
+        let mut members: Vec<Vec<usize>> = (0..self.len()).map(|i| vec![i]).collect();
+        let mut active: Vec<usize> = (0..self.len()).collect();
+
+        let mut best = (usize::MAX, vec![]);
+        while active.len() > 1 {
+            let (second_last, last, cut) = minimum_cut_phase(&adjacency, &active);
+            if cut < best.0 {
+                best = (cut, members[last].clone());
+            }
+
+            // Merge the last node into the second last one
+            for (other, weight) in std::mem::take(&mut adjacency[last]) {
+                adjacency[other].remove(&last);
+                if other != second_last {
+                    *adjacency[second_last].entry(other).or_default() += weight;
+                    *adjacency[other].entry(second_last).or_default() += weight;
+                }
+            }
+            let moved = std::mem::take(&mut members[last]);
+            members[second_last].extend(moved);
+            active.retain(|&i| i != last);
+        }
+
+        (best.0, best.1.into_iter().map(|i| &self.nodes[i]).collect())
+    }
+
+    /// Length of the longest path from `start` to `end` that doesn't visit any node twice.
+    ///
+    /// Brute forces every path with the visited nodes stored in a bitmask so it only works for
+    /// graphs with at most 128 nodes, [`contract()`][Graph::contract] the graph first if it's made
+    /// out of long corridors.
+    pub fn longest_path(&self, start: usize, end: usize) -> Option<usize> {
+        assert!(self.len() <= 128, "Too many nodes for the bitmask");
+        let mut longest = None;
+        let mut stack = vec![(start, 1_u128 << start, 0)];
+        while let Some((cur, visited, distance)) = stack.pop() {
+            if cur == end {
+                longest = longest.max(Some(distance));
+                continue;
+            }
+            for &(next, weight) in &self.edges[cur] {
+                if visited & (1 << next) == 0 {
+                    stack.push((next, visited | (1 << next), distance + weight));
+                }
+            }
+        }
+        longest
+    }
+
+    /// Removes every node that is only a part of a corridor.
+    ///
+    /// A node is part of a corridor if it's connected to exactly 2 other nodes, those get replaced
+    /// by a single edge with the weights added together. Nodes for which `keep` returns `true`
+    /// are never removed. Works with directed edges, a corridor that can't be walked all the way
+    /// in one direction doesn't get an edge in that direction.
+    pub fn contract<F>(&self, keep: F) -> Self
+    where
+        F: Fn(&N) -> bool,
+    {
+        let neighbours = self.undirected_neighbours();
+        let is_junction = |i: usize| neighbours[i].len() != 2 || keep(&self.nodes[i]);
+
+        let mut contracted = Self::new();
+        for start in (0..self.len()).filter(|&i| is_junction(i)) {
+            contracted.add_node(self.nodes[start].clone());
+            for &(first, weight) in &self.edges[start] {
+                let mut previous = start;
+                let mut cur = first;
+                let mut distance = weight;
+                // Follow the corridor until the next junction
+                while !is_junction(cur) {
+                    match self.edges[cur].iter().find(|(next, _)| *next != previous) {
+                        Some(&(next, weight)) => {
+                            previous = cur;
+                            cur = next;
+                            distance += weight;
+                        }
+                        None => break,
+                    }
+                }
+                if is_junction(cur) && cur != start {
+                    contracted.add_directed_edge(
+                        self.nodes[start].clone(),
+                        self.nodes[cur].clone(),
+                        distance,
+                    );
+                }
+            }
+        }
+        contracted
+    }
+
+    /// The nodes connected to every node, ignoring the direction of the edges.
+    fn undirected_neighbours(&self) -> Vec<HashSet<usize>> {
+        let mut neighbours = vec![HashSet::new(); self.len()];
+        for (from, edges) in self.edges.iter().enumerate() {
+            for &(to, _) in edges {
+                if from != to {
+                    neighbours[from].insert(to);
+                    neighbours[to].insert(from);
+                }
+            }
+        }
+        neighbours
+    }
+}
+
+impl Graph<(usize, usize)> {
+    /// Creates a graph out of a grid where every tile is a node.
+    ///
+    /// `can_move(from, to, direction)` decides if there is an edge between 2 tiles that are next
+    /// to each other, every edge has a weight of 1. Only tiles that have an edge become nodes.
+    pub fn from_grid<T, F>(grid: &[Vec<T>], can_move: F) -> Self
+    where
+        F: Fn(&T, &T, Direction) -> bool,
+    {
+        let mut graph = Self::new();
+        for (y, row) in grid.iter().enumerate() {
+            for (x, tile) in row.iter().enumerate() {
+                for (direction, modif) in Direction::CARDINAL.into_iter().zip(CARDINAL_MODIF) {
+                    let (Some(new_x), Some(new_y)) =
+                        (x.checked_add_signed(modif.0), y.checked_add_signed(modif.1))
+                    else {
+                        continue;
+                    };
+                    if new_y >= grid.len() || new_x >= grid[new_y].len() {
+                        continue;
+                    }
+                    if can_move(tile, &grid[new_y][new_x], direction) {
+                        graph.add_directed_edge((x, y), (new_x, new_y), 1);
+                    }
+                }
+            }
+        }
+        graph
+    }
+}
+
+impl<N> Default for Graph<N>
+where
+    N: Hash + Eq + Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn bron_kerbosch(
+    neighbours: &[HashSet<usize>],
+    current: &mut Vec<usize>,
+    mut candidates: HashSet<usize>,
+    mut excluded: HashSet<usize>,
+    best: &mut Vec<usize>,
+) {
+    if candidates.is_empty() {
+        if excluded.is_empty() && current.len() > best.len() {
+            *best = current.clone();
+        }
+        return;
+    }
+    // Can't beat the best one anymore
+    if current.len() + candidates.len() <= best.len() {
+        return;
+    }
+
+    // Pivot with the most neighbours in candidates means the least amount of calls
+    let pivot = *candidates
+        .union(&excluded)
+        .max_by_key(|&&p| neighbours[p].intersection(&candidates).count())
+        .unwrap();
+    let to_check: Vec<usize> = candidates.difference(&neighbours[pivot]).copied().collect();
+
+    for node in to_check {
+        current.push(node);
+        bron_kerbosch(
+            neighbours,
+            current,
+            candidates
+                .intersection(&neighbours[node])
+                .copied()
+                .collect(),
+            excluded.intersection(&neighbours[node]).copied().collect(),
+            best,
+        );
+        current.pop();
+        candidates.remove(&node);
+        excluded.insert(node);
+    }
+}
+
+/// One phase of Stoer–Wagner.
+///
+/// Keeps adding the node that is the most connected to the already added ones. Returns the last
+/// 2 added nodes and the weight of the cut that separates the last one from everything else.
+fn minimum_cut_phase(
+    adjacency: &[HashMap<usize, usize>],
+    active: &[usize],
+) -> (usize, usize, usize) {
+    let mut connection: HashMap<usize, usize> = active.iter().map(|&i| (i, 0)).collect();
+    let mut added: HashSet<usize> = HashSet::new();
+    let mut heap: BinaryHeap<(usize, Reverse<usize>)> =
+        active.iter().map(|&i| (0, Reverse(i))).collect();
+    let (mut second_last, mut last, mut cut) = (active[0], active[0], 0);
+
+    while let Some((weight, Reverse(node))) = heap.pop() {
+        if added.contains(&node) || weight != connection[&node] {
+            continue;
+        }
+        added.insert(node);
+        (second_last, last, cut) = (last, node, weight);
+        for (&other, &edge) in &adjacency[node] {
+            if !added.contains(&other) {
+                let value = connection.get_mut(&other).unwrap();
+                *value += edge;
+                heap.push((*value, Reverse(other)));
+            }
+        }
+    }
+    (second_last, last, cut)
+}
+
+#[cfg(test)]
+mod graph_tests {
+    use super::*;
+
+    fn from_pairs(pairs: &[(&'static str, &'static str)]) -> Graph<&'static str> {
+        let mut graph = Graph::new();
+        for &(a, b) in pairs {
+            graph.add_edge(a, b, 1);
+        }
+        graph
+    }
+
+    #[test]
+    fn nodes() {
+        let mut graph = Graph::new();
+        assert!(graph.is_empty());
+        assert_eq!(graph.add_node("a"), 0);
+        assert_eq!(graph.add_node("b"), 1);
+        assert_eq!(graph.add_node("a"), 0);
+        graph.add_directed_edge("a", "c", 5);
+        assert_eq!(graph.len(), 3);
+        assert_eq!(graph.index_of(&"c"), Some(2));
+        assert_eq!(graph.node(2), &"c");
+        assert_eq!(graph.neighbours(0), &[(2, 5)]);
+        assert!(graph.neighbours(2).is_empty());
+    }
+
+    #[test]
+    fn clique() {
+        // 2024 day 23 example
+        let graph = from_pairs(&[
+            ("kh", "tc"),
+            ("qp", "kh"),
+            ("de", "cg"),
+            ("ka", "co"),
+            ("yn", "aq"),
+            ("qp", "ub"),
+            ("cg", "tb"),
+            ("vc", "aq"),
+            ("tb", "ka"),
+            ("wh", "tc"),
+            ("yn", "cg"),
+            ("kh", "ub"),
+            ("ta", "co"),
+            ("de", "co"),
+            ("tc", "td"),
+            ("tb", "wq"),
+            ("wh", "td"),
+            ("ta", "ka"),
+            ("td", "qp"),
+            ("aq", "cg"),
+            ("wq", "ub"),
+            ("ub", "vc"),
+            ("de", "ta"),
+            ("wq", "aq"),
+            ("wq", "vc"),
+            ("wh", "yn"),
+            ("ka", "de"),
+            ("kh", "ta"),
+            ("co", "tc"),
+            ("wh", "qp"),
+            ("tb", "vc"),
+            ("td", "yn"),
+        ]);
+        let mut clique = graph.max_clique();
+        clique.sort_unstable();
+        assert_eq!(clique, [&"co", &"de", &"ka", &"ta"]);
+    }
+
+    #[test]
+    fn cut() {
+        // 2023 day 25 example
+        let mut graph = Graph::new();
+        for line in [
+            "jqt: rhn xhk nvd",
+            "rsh: frs pzl lsr",
+            "xhk: hfx",
+            "cmg: qnr nvd lhk bvb",
+            "rhn: xhk bvb hfx",
+            "bvb: xhk hfx",
+            "pzl: lsr hfx nvd",
+            "qnr: nvd",
+            "ntq: jqt hfx bvb xhk",
+            "nvd: lhk",
+            "lsr: lhk",
+            "rzs: qnr cmg lsr rsh",
+            "frs: qnr lhk lsr",
+        ] {
+            let (from, rest) = line.split_once(": ").unwrap();
+            for to in rest.split(' ') {
+                graph.add_edge(from, to, 1);
+            }
+        }
+        let (weight, side) = graph.min_cut();
+        assert_eq!(weight, 3);
+        assert_eq!(side.len() * (graph.len() - side.len()), 54);
+    }
+
+    #[test]
+    fn disconnected_cut() {
+        let graph = from_pairs(&[("a", "b"), ("c", "d")]);
+        assert_eq!(graph.min_cut().0, 0);
+    }
+
+    #[test]
+    fn corridors() {
+        #[rustfmt::skip]
+        let grid: Vec<Vec<char>> = [
+            "#.#####",
+            "#.....#",
+            "#.###.#",
+            "#.....#",
+            "#####.#",
+        ]
+        .iter()
+        .map(|l| l.chars().collect())
+        .collect();
+        let graph = Graph::from_grid(&grid, |from, to, _| *from != '#' && *to != '#');
+        let contracted = graph.contract(|_| false);
+        // Start, end and the 2 junctions
+        assert_eq!(contracted.len(), 4);
+
+        let start = contracted.index_of(&(1, 0)).unwrap();
+        let end = contracted.index_of(&(5, 4)).unwrap();
+        assert_eq!(contracted.longest_path(start, end), Some(8));
+        assert_eq!(
+            graph.longest_path(
+                graph.index_of(&(1, 0)).unwrap(),
+                graph.index_of(&(5, 4)).unwrap()
+            ),
+            Some(8)
+        );
+    }
+
+    #[test]
+    fn one_way_corridor() {
+        let grid: Vec<Vec<char>> = ["..>..", ".###.", "....."]
+            .iter()
+            .map(|l| l.chars().collect())
+            .collect();
+        let graph = Graph::from_grid(&grid, |from, to, direction| match from {
+            '#' => false,
+            '>' => direction == Direction::E && *to != '#',
+            _ => *to != '#' && !(*to == '>' && direction == Direction::W),
+        });
+        let contracted = graph.contract(|&p| p == (0, 0) || p == (4, 0));
+        let start = contracted.index_of(&(0, 0)).unwrap();
+        let end = contracted.index_of(&(4, 0)).unwrap();
+        assert_eq!(contracted.longest_path(start, end), Some(8));
+        // Going back can only be done the long way
+        assert_eq!(contracted.longest_path(end, start), Some(8));
+        assert_eq!(contracted.neighbours(start).len(), 2);
+        assert_eq!(contracted.neighbours(end).len(), 1);
+    }
+}
@@ -0,0 +1,216 @@
+//! MD5 helpers for the puzzles that look for hashes with some property.
+//!
+//! [`NonceSearch`] hashes `key` followed by increasing numbers on all threads and gives back the
+//! numbers whose hash matches a predicate, in order.
+
+use std::collections::VecDeque;
+use std::num::NonZeroUsize;
+use std::thread;
+
+use md5::{Digest as _, Md5};
+
+pub type Digest = [u8; 16];
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+pub fn md5(data: impl AsRef<[u8]>) -> Digest {
+    Md5::digest(data).into()
+}
+
+/// Turns bytes into a lowercase hex string.
+pub fn to_hex(bytes: &[u8]) -> String {
+    let mut hex = String::with_capacity(bytes.len() * 2);
+    for b in bytes {
+        hex.push(HEX_DIGITS[(b >> 4) as usize] as char);
+        hex.push(HEX_DIGITS[(b & 0xF) as usize] as char);
+    }
+    hex
+}
+
+/// The nth hex digit of the digest.
+pub fn nibble(digest: &Digest, n: usize) -> u8 {
+    let byte = digest[n / 2];
+    if n.is_multiple_of(2) {
+        byte >> 4
+    } else {
+        byte & 0xF
+    }
+}
+
+/// Checks if the hex string of the digest starts with `n` zeros.
+pub fn starts_with_zeros(digest: &Digest, n: usize) -> bool {
+    (0..n).all(|i| nibble(digest, i) == 0)
+}
+
+/// Hashes the lowercase hex string of the digest again `rounds` times.
+pub fn stretch(mut digest: Digest, rounds: usize) -> Digest {
+    for _ in 0..rounds {
+        digest = md5(to_hex(&digest));
+    }
+    digest
+}
+
+/// Iterator over the numbers whose hash matches the predicate.
+///
+/// Every thread gets a chunk of numbers, once all of them are done the matches are returned in
+/// order and the next chunks are started.
+pub struct NonceSearch<F> {
+    key: String,
+    predicate: F,
+    rounds: usize,
+    threads: usize,
+    chunk_size: u64,
+    next: u64,
+    found: VecDeque<(u64, Digest)>,
+}
+
+impl<F> NonceSearch<F>
+where
+    F: Fn(&Digest) -> bool + Sync,
+{
+    pub fn new(key: impl Into<String>, predicate: F) -> Self {
+        Self {
+            key: key.into(),
+            predicate,
+            rounds: 0,
+            threads: thread::available_parallelism().map_or(1, NonZeroUsize::get),
+            chunk_size: 4096,
+            next: 0,
+            found: VecDeque::new(),
+        }
+    }
+
+    /// The first number to hash, 0 by default.
+    pub fn start(mut self, start: u64) -> Self {
+        self.next = start;
+        self
+    }
+
+    /// Stretches every hash with [`stretch()`][stretch] before checking it.
+    pub fn stretched(mut self, rounds: usize) -> Self {
+        self.rounds = rounds;
+        self
+    }
+
+    /// Uses the given amount of threads instead of all of them.
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// How many numbers every thread hashes at once.
+    pub fn chunk_size(mut self, chunk_size: u64) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    fn search_chunk(&self, start: u64) -> Vec<(u64, Digest)> {
+        let prefix = Md5::new_with_prefix(&self.key);
+        (start..start + self.chunk_size)
+            .filter_map(|nonce| {
+                let mut hasher = prefix.clone();
+                hasher.update(nonce.to_string());
+                let digest = stretch(hasher.finalize().into(), self.rounds);
+                (self.predicate)(&digest).then_some((nonce, digest))
+            })
+            .collect()
+    }
+}
+
+impl<F> Iterator for NonceSearch<F>
+where
+    F: Fn(&Digest) -> bool + Sync,
+{
+    type Item = (u64, Digest);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.found.is_empty() {
+            let starts: Vec<u64> = (0..self.threads as u64)
+                .map(|i| self.next + i * self.chunk_size)
+                .collect();
+            let this = &*self;
+            let results: Vec<Vec<(u64, Digest)>> = thread::scope(|scope| {
+                let handles: Vec<_> = starts
+                    .iter()
+                    .map(|&start| scope.spawn(move || this.search_chunk(start)))
+                    .collect();
+                handles.into_iter().map(|h| h.join().unwrap()).collect()
+            });
+            self.next += self.threads as u64 * self.chunk_size;
+            self.found.extend(results.into_iter().flatten());
+        }
+        self.found.pop_front()
+    }
+}
+
+#[cfg(test)]
+mod hash_tests {
+    use super::*;
+
+    #[test]
+    fn hex() {
+        assert_eq!(to_hex(&md5("")), "d41d8cd98f00b204e9800998ecf8427e");
+        assert_eq!(to_hex(&[0x00, 0xAB, 0x0F]), "00ab0f");
+    }
+
+    #[test]
+    fn nibbles() {
+        let digest = md5("abcdef609043");
+        assert_eq!(&to_hex(&digest)[..6], "000001");
+        assert!(starts_with_zeros(&digest, 5));
+        assert!(!starts_with_zeros(&digest, 6));
+        assert_eq!(nibble(&digest, 5), 1);
+    }
+
+    #[test]
+    fn stretched() {
+        // 2016 day 14 example
+        let digest = md5("abc0");
+        assert_eq!(to_hex(&stretch(digest, 0)), to_hex(&digest));
+        assert_eq!(
+            to_hex(&stretch(digest, 2016)),
+            "a107ff634856bb300138cac6568c0f24"
+        );
+    }
+
+    #[test]
+    fn search() {
+        // 2015 day 4 example, starting close to the answer so the test doesn't take long
+        let mut search = NonceSearch::new("abcdef", |d| starts_with_zeros(d, 5)).start(600_000);
+        assert_eq!(search.next().map(|r| r.0), Some(609043));
+
+        // The results should be the same with any amount of threads
+        let predicate = |d: &Digest| starts_with_zeros(d, 2);
+        let expected: Vec<u64> = (0..)
+            .filter(|n| predicate(&md5(format!("abc{n}"))))
+            .take(20)
+            .collect();
+        for threads in [1, 3, 8] {
+            let found: Vec<u64> = NonceSearch::new("abc", predicate)
+                .threads(threads)
+                .chunk_size(100)
+                .take(20)
+                .map(|r| r.0)
+                .collect();
+            assert_eq!(found, expected);
+        }
+    }
+
+    #[test]
+    fn search_stretched() {
+        let predicate = |d: &Digest| nibble(d, 0) == 0xa;
+        let found: Vec<(u64, Digest)> = NonceSearch::new("abc", predicate)
+            .stretched(3)
+            .chunk_size(7)
+            .take(5)
+            .collect();
+        for (nonce, digest) in &found {
+            assert_eq!(*digest, stretch(md5(format!("abc{nonce}")), 3));
+        }
+        let expected: Vec<u64> = (0..)
+            .filter(|n| predicate(&stretch(md5(format!("abc{n}")), 3)))
+            .take(5)
+            .collect();
+        assert_eq!(found.iter().map(|r| r.0).collect::<Vec<_>>(), expected);
+    }
+}
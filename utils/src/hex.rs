@@ -0,0 +1,384 @@
+//! Hexagonal grids with pointy topped hexes.
+//!
+//! Positions use axial coordinates `(q, r)` where `q` grows to the east and `r` to the south east.
+//! The third cube coordinate `s` is always `-q - r`.
+
+use std::collections::{HashMap, HashSet};
+use std::ops::{Add, AddAssign, Deref, Mul, Sub};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Hex {
+    pub q: isize,
+    pub r: isize,
+}
+
+impl Hex {
+    pub const ORIGIN: Self = Self { q: 0, r: 0 };
+
+    pub fn new(q: isize, r: isize) -> Self {
+        Self { q, r }
+    }
+
+    /// Creates the hex from cube coordinates, `None` if they don't add up to 0.
+    pub fn from_cube(q: isize, r: isize, s: isize) -> Option<Self> {
+        (q + r + s == 0).then_some(Self { q, r })
+    }
+
+    pub fn s(&self) -> isize {
+        -self.q - self.r
+    }
+
+    /// (q, r, s)
+    pub fn cube(&self) -> (isize, isize, isize) {
+        (self.q, self.r, self.s())
+    }
+
+    pub fn neighbour(&self, direction: HexDirection) -> Self {
+        *self + direction.offset()
+    }
+
+    /// In the same order as [`HexDirection::ALL`].
+    pub fn neighbours(&self) -> [Self; 6] {
+        HexDirection::ALL.map(|d| self.neighbour(d))
+    }
+
+    /// Smallest amount of steps needed to get to the other hex.
+    pub fn distance(&self, other: &Self) -> usize {
+        (*self - *other).length()
+    }
+
+    /// Distance from the origin.
+    pub fn length(&self) -> usize {
+        (self.q.unsigned_abs() + self.r.unsigned_abs() + self.s().unsigned_abs()) / 2
+    }
+
+    /// Follows the directions one after another.
+    pub fn walk<I>(&self, path: I) -> Self
+    where
+        I: IntoIterator<Item = HexDirection>,
+    {
+        path.into_iter().fold(*self, |hex, d| hex.neighbour(d))
+    }
+
+    /// All hexes exactly `radius` steps away, going clockwise starting from the west.
+    pub fn ring(&self, radius: usize) -> Vec<Self> {
+        if radius == 0 {
+            return vec![*self];
+        }
+        let mut ring = Vec::with_capacity(6 * radius);
+        let mut cur = *self + HexDirection::W.offset() * radius as isize;
+        for direction in [
+            HexDirection::NE,
+            HexDirection::E,
+            HexDirection::SE,
+            HexDirection::SW,
+            HexDirection::W,
+            HexDirection::NW,
+        ] {
+            for _ in 0..radius {
+                ring.push(cur);
+                cur = cur.neighbour(direction);
+            }
+        }
+        ring
+    }
+
+    /// All hexes at most `radius` steps away, ring by ring starting with this one.
+    pub fn range(&self, radius: usize) -> Vec<Self> {
+        (0..=radius).flat_map(|r| self.ring(r)).collect()
+    }
+}
+
+impl Add for Hex {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(self.q + rhs.q, self.r + rhs.r)
+    }
+}
+
+impl AddAssign for Hex {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Hex {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::new(self.q - rhs.q, self.r - rhs.r)
+    }
+}
+
+impl Mul<isize> for Hex {
+    type Output = Self;
+
+    fn mul(self, rhs: isize) -> Self::Output {
+        Self::new(self.q * rhs, self.r * rhs)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum HexDirection {
+    E,
+    SE,
+    SW,
+    W,
+    NW,
+    NE,
+}
+
+impl HexDirection {
+    /// Clockwise starting from E
+    pub const ALL: [Self; 6] = [Self::E, Self::SE, Self::SW, Self::W, Self::NW, Self::NE];
+
+    pub fn offset(&self) -> Hex {
+        match self {
+            HexDirection::E => Hex::new(1, 0),
+            HexDirection::SE => Hex::new(0, 1),
+            HexDirection::SW => Hex::new(-1, 1),
+            HexDirection::W => Hex::new(-1, 0),
+            HexDirection::NW => Hex::new(0, -1),
+            HexDirection::NE => Hex::new(1, -1),
+        }
+    }
+
+    pub fn turn_right(&self) -> Self {
+        Self::ALL[(usize::from(*self) + 1) % 6]
+    }
+
+    pub fn turn_left(&self) -> Self {
+        Self::ALL[(usize::from(*self) + 5) % 6]
+    }
+
+    pub fn opposite(&self) -> Self {
+        Self::ALL[(usize::from(*self) + 3) % 6]
+    }
+
+    /// Parses directions written one after another without anything between them, `esenee`.
+    ///
+    /// `None` if there is something that isn't a direction.
+    pub fn parse_path(s: &str) -> Option<Vec<Self>> {
+        let mut path = vec![];
+        let mut rest = s.trim();
+        while !rest.is_empty() {
+            let len = if rest.starts_with(['n', 's']) { 2 } else { 1 };
+            path.push(rest.get(..len)?.parse().ok()?);
+            rest = &rest[len..];
+        }
+        Some(path)
+    }
+}
+
+impl From<HexDirection> for usize {
+    fn from(value: HexDirection) -> Self {
+        match value {
+            HexDirection::E => 0,
+            HexDirection::SE => 1,
+            HexDirection::SW => 2,
+            HexDirection::W => 3,
+            HexDirection::NW => 4,
+            HexDirection::NE => 5,
+        }
+    }
+}
+
+impl FromStr for HexDirection {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "e" => Self::E,
+            "se" => Self::SE,
+            "sw" => Self::SW,
+            "w" => Self::W,
+            "nw" => Self::NW,
+            "ne" => Self::NE,
+            _ => return Err(()),
+        })
+    }
+}
+
+/// Set of turned on hexes, for cellular automata on a hex grid.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HexSet(HashSet<Hex>);
+
+impl HexSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, hex: Hex) -> bool {
+        self.0.insert(hex)
+    }
+
+    pub fn remove(&mut self, hex: &Hex) -> bool {
+        self.0.remove(hex)
+    }
+
+    /// Flips the hex, returns true if it's turned on now.
+    pub fn toggle(&mut self, hex: Hex) -> bool {
+        if self.0.remove(&hex) {
+            false
+        } else {
+            self.0.insert(hex);
+            true
+        }
+    }
+
+    pub fn into_inner(self) -> HashSet<Hex> {
+        self.0
+    }
+
+    /// How many turned on neighbours every hex has, hexes with none aren't included.
+    pub fn neighbour_counts(&self) -> HashMap<Hex, usize> {
+        let mut counts = HashMap::with_capacity(self.0.len() * 6);
+        for hex in &self.0 {
+            for neighbour in hex.neighbours() {
+                *counts.entry(neighbour).or_default() += 1;
+            }
+        }
+        counts
+    }
+
+    /// Calculates the next generation.
+    ///
+    /// `rule` gets if the hex is on and how many of its neighbours are on and says if it should be
+    /// on in the next one. Hexes without any turned on neighbours always turn off.
+    pub fn step<F>(&self, rule: F) -> Self
+    where
+        F: Fn(bool, usize) -> bool,
+    {
+        let mut next = HashSet::with_capacity(self.0.len());
+        for (hex, count) in self.neighbour_counts() {
+            if rule(self.0.contains(&hex), count) {
+                next.insert(hex);
+            }
+        }
+        Self(next)
+    }
+}
+
+impl FromIterator<Hex> for HexSet {
+    fn from_iter<T: IntoIterator<Item = Hex>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl Deref for HexSet {
+    type Target = HashSet<Hex>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod hex_tests {
+    use super::*;
+
+    #[test]
+    fn directions() {
+        assert_eq!(
+            HexDirection::parse_path("esenee"),
+            Some(vec![
+                HexDirection::E,
+                HexDirection::SE,
+                HexDirection::NE,
+                HexDirection::E
+            ])
+        );
+        assert_eq!(HexDirection::parse_path("n"), None);
+        assert_eq!(HexDirection::parse_path("ex"), None);
+        assert_eq!(HexDirection::NE.turn_right(), HexDirection::E);
+        assert_eq!(HexDirection::E.turn_left(), HexDirection::NE);
+        assert_eq!(HexDirection::NW.opposite(), HexDirection::SE);
+        for d in HexDirection::ALL {
+            assert_eq!(d.offset() + d.opposite().offset(), Hex::ORIGIN);
+        }
+    }
+
+    #[test]
+    fn walk() {
+        let path = HexDirection::parse_path("nwwswee").unwrap();
+        assert_eq!(Hex::ORIGIN.walk(path), Hex::ORIGIN);
+        let path = HexDirection::parse_path("esew").unwrap();
+        assert_eq!(
+            Hex::ORIGIN.walk(path),
+            Hex::ORIGIN.neighbour(HexDirection::SE)
+        );
+    }
+
+    #[test]
+    fn distance() {
+        let hex = Hex::from_cube(3, -1, -2).unwrap();
+        assert_eq!(hex.cube(), (3, -1, -2));
+        assert_eq!(hex.length(), 3);
+        assert_eq!(hex.distance(&Hex::new(-2, 1)), 5);
+        assert_eq!(Hex::from_cube(1, 1, 1), None);
+        for neighbour in hex.neighbours() {
+            assert_eq!(hex.distance(&neighbour), 1);
+        }
+    }
+
+    #[test]
+    fn rings() {
+        let center = Hex::new(2, -5);
+        assert_eq!(center.ring(0), [center]);
+        for radius in 1..5 {
+            let ring = center.ring(radius);
+            assert_eq!(ring.len(), 6 * radius);
+            assert_eq!(ring.iter().collect::<HashSet<_>>().len(), ring.len());
+            assert!(ring.iter().all(|h| h.distance(&center) == radius));
+            // Every hex is next to the one before it
+            for i in 0..ring.len() {
+                assert_eq!(ring[i].distance(&ring[(i + 1) % ring.len()]), 1);
+            }
+        }
+        let range = center.range(3);
+        assert_eq!(range.len(), 1 + 6 + 12 + 18);
+        assert_eq!(range.iter().collect::<HashSet<_>>().len(), range.len());
+    }
+
+    /// 2020 day 24 example
+    #[test]
+    fn lobby_layout() {
+        let tiles = [
+            "sesenwnenenewseeswwswswwnenewsewsw",
+            "neeenesenwnwwswnenewnwwsewnenwseswesw",
+            "seswneswswsenwwnwse",
+            "nwnwneseeswswnenewneswwnewseswneseene",
+            "swweswneswnenwsewnwneneseenw",
+            "eesenwseswswnenwswnwnwsewwnwsene",
+            "sewnenenenesenwsewnenwwwse",
+            "wenwwweseeeweswwwnwwe",
+            "wsweesenenewnwwnwsenewsenwwsesesenwne",
+            "neeswseenwwswnwswswnw",
+            "nenwswwsewswnenenewsenwsenwnesesenew",
+            "enewnwewneswsewnwswenweswnenwsenwsw",
+            "sweneswneswneneenwnewenewwneswswnese",
+            "swwesenesewenwneswnwwneseswwne",
+            "enesenwswwswneneswsenwnewswseenwsese",
+            "wnwnesenesenenwwnenwsewesewsesesew",
+            "nenewswnwewswnenesenwnesewesw",
+            "eneswnwswnwsenenwnwnwwseeswneewsenese",
+            "neswnwewnwnwseenwseesewsenwsweewe",
+            "wseweeenwnesenwwwswnew",
+        ];
+        let mut black = HexSet::new();
+        for tile in tiles {
+            black.toggle(Hex::ORIGIN.walk(HexDirection::parse_path(tile).unwrap()));
+        }
+        assert_eq!(black.len(), 10);
+
+        let rule = |is_black: bool, count: usize| count == 2 || (is_black && count == 1);
+        let mut day = black.step(rule);
+        assert_eq!(day.len(), 15);
+        for _ in 1..10 {
+            day = day.step(rule);
+        }
+        assert_eq!(day.len(), 37);
+    }
+}
@@ -1,12 +1,24 @@
+use std::cmp::Reverse;
 use std::collections::HashMap;
 use std::fmt::Display;
 use std::hash::Hash;
 use std::io::BufRead;
 use std::ops::Deref;
-use std::cmp::Reverse;
 
+pub mod assembunny;
+pub mod bits;
+pub mod circuit;
 pub mod cursor;
+pub mod geometry;
+pub mod graph;
+pub mod hash;
+pub mod hex;
+pub mod linalg;
 pub mod math;
+pub mod ocr;
+pub mod render;
+pub mod tsp;
+pub mod vm;
 
 pub type Grid<T> = Vec<Vec<T>>;
 
@@ -213,9 +225,9 @@ where
             }
         }
         GridBorderType::RemoveBorder => {
-            for y in 1..(grid.len() - 1) {
-                for x in 1..(grid[0].len() - 1) {
-                    print!("{}", grid[y][x])
+            for line in &grid[1..(grid.len() - 1)] {
+                for tile in &line[1..(line.len() - 1)] {
+                    print!("{tile}")
                 }
                 println!();
             }
@@ -223,19 +235,14 @@ where
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Hash)]
+#[derive(Debug, PartialEq, Eq, Hash, Default)]
 pub enum GridBorderType<'a> {
     AddBorder(&'a str),
+    #[default]
     AsIs,
     RemoveBorder,
 }
 
-impl Default for GridBorderType<'_> {
-    fn default() -> Self {
-        Self::AsIs
-    }
-}
-
 pub trait BufReadExt: BufRead {
     /// Returns an iterator over the lines of this reader.
     ///
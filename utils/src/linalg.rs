@@ -0,0 +1,417 @@
+//! Exact solving of linear equation systems.
+//!
+//! Everything is done with [`Rational`] numbers so there are no floating point errors, which
+//! matters when the puzzle numbers are as big as they usually are.
+
+use std::cmp::Ordering;
+use std::fmt::Display;
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+
+/// Signed integer types that can be used in a [`Rational`].
+pub trait Integer:
+    Copy
+    + Ord
+    + Display
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+    + Neg<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn abs(self) -> Self {
+        if self < Self::ZERO {
+            -self
+        } else {
+            self
+        }
+    }
+
+    /// Greatest common divisor, always positive unless both are 0.
+    fn gcd(self, other: Self) -> Self {
+        let (mut a, mut b) = (self.abs(), other.abs());
+        while b != Self::ZERO {
+            (a, b) = (b, a % b);
+        }
+        a
+    }
+}
+
+macro_rules! integer_impl {
+    ($($type:ty),*) => {
+        $(
+            impl Integer for $type {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+            }
+        )*
+    };
+}
+
+integer_impl!(i8, i16, i32, i64, i128, isize);
+
+/// Why a system couldn't be solved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LinalgError {
+    /// There is no solution or there are infinitely many.
+    Singular,
+    /// There is exactly one solution but it's not made of whole numbers.
+    NoIntegerSolution,
+}
+
+/// A fraction that is always kept in its simplest form with a positive denominator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational<T = i128> {
+    num: T,
+    den: T,
+}
+
+impl<T: Integer> Rational<T> {
+    pub const ZERO: Self = Self {
+        num: T::ZERO,
+        den: T::ONE,
+    };
+    pub const ONE: Self = Self {
+        num: T::ONE,
+        den: T::ONE,
+    };
+
+    /// Panics if `den` is 0.
+    pub fn new(num: T, den: T) -> Self {
+        assert!(den != T::ZERO, "Denominator can't be 0");
+        let divisor = num.gcd(den);
+        let (num, den) = (num / divisor, den / divisor);
+        if den < T::ZERO {
+            Self {
+                num: -num,
+                den: -den,
+            }
+        } else {
+            Self { num, den }
+        }
+    }
+
+    pub fn numer(&self) -> T {
+        self.num
+    }
+
+    pub fn denom(&self) -> T {
+        self.den
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.num == T::ZERO
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.den == T::ONE
+    }
+
+    /// The value as a whole number if it is one.
+    pub fn to_integer(&self) -> Option<T> {
+        self.is_integer().then_some(self.num)
+    }
+
+    /// Panics if the value is 0.
+    pub fn recip(&self) -> Self {
+        Self::new(self.den, self.num)
+    }
+}
+
+impl<T: Integer> From<T> for Rational<T> {
+    fn from(value: T) -> Self {
+        Self {
+            num: value,
+            den: T::ONE,
+        }
+    }
+}
+
+impl<T: Integer> Add for Rational<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let divisor = self.den.gcd(rhs.den);
+        let den = self.den / divisor * rhs.den;
+        Self::new(self.num * (den / self.den) + rhs.num * (den / rhs.den), den)
+    }
+}
+
+impl<T: Integer> Sub for Rational<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + -rhs
+    }
+}
+
+impl<T: Integer> Mul for Rational<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        // Simplify first to keep the numbers small
+        let first = self.num.gcd(rhs.den);
+        let second = rhs.num.gcd(self.den);
+        let (first, second) = (
+            if first == T::ZERO { T::ONE } else { first },
+            if second == T::ZERO { T::ONE } else { second },
+        );
+        Self::new(
+            (self.num / first) * (rhs.num / second),
+            (self.den / second) * (rhs.den / first),
+        )
+    }
+}
+
+impl<T: Integer> Div for Rational<T> {
+    type Output = Self;
+
+    /// Panics when dividing by 0.
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self::Output {
+        self * rhs.recip()
+    }
+}
+
+impl<T: Integer> Neg for Rational<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            num: -self.num,
+            den: self.den,
+        }
+    }
+}
+
+impl<T: Integer> PartialOrd for Rational<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Integer> Ord for Rational<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.num * other.den).cmp(&(other.num * self.den))
+    }
+}
+
+impl<T: Integer> Display for Rational<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
+/// Solves `matrix * x = rhs` with Gauss-Jordan elimination.
+///
+/// `matrix` has to be square and have as many rows as `rhs`. Returns
+/// [`LinalgError::Singular`] if there isn't exactly one solution.
+pub fn solve<T: Integer>(matrix: &[Vec<T>], rhs: &[T]) -> Result<Vec<Rational<T>>, LinalgError> {
+    let size = rhs.len();
+    assert_eq!(matrix.len(), size, "Matrix needs as many rows as the rhs");
+    assert!(
+        matrix.iter().all(|row| row.len() == size),
+        "Matrix needs to be square"
+    );
+
+    // Augmented matrix
+    let mut rows: Vec<Vec<Rational<T>>> = matrix
+        .iter()
+        .zip(rhs)
+        .map(|(row, value)| {
+            row.iter()
+                .chain(std::iter::once(value))
+                .map(|&n| Rational::from(n))
+                .collect()
+        })
+        .collect();
+
+    for column in 0..size {
+        let pivot = (column..size)
+            .find(|&r| !rows[r][column].is_zero())
+            .ok_or(LinalgError::Singular)?;
+        rows.swap(column, pivot);
+
+        let divisor = rows[column][column];
+        for value in rows[column].iter_mut() {
+            *value = *value / divisor;
+        }
+
+        for other in 0..size {
+            if other == column || rows[other][column].is_zero() {
+                continue;
+            }
+            let factor = rows[other][column];
+            let pivot_row = rows[column].clone();
+            for (value, pivot_value) in rows[other].iter_mut().zip(pivot_row).skip(column) {
+                *value = *value - pivot_value * factor;
+            }
+        }
+    }
+
+    Ok(rows.into_iter().map(|row| row[size]).collect())
+}
+
+/// Same as [`solve()`][solve] but only accepts a solution made of whole numbers.
+pub fn solve_integer<T: Integer>(matrix: &[Vec<T>], rhs: &[T]) -> Result<Vec<T>, LinalgError> {
+    solve(matrix, rhs)?
+        .into_iter()
+        .map(|value| value.to_integer().ok_or(LinalgError::NoIntegerSolution))
+        .collect()
+}
+
+/// Solves a 2x2 system with whole numbers using Cramer's rule.
+///
+/// ```text
+/// matrix[0][0] * x + matrix[0][1] * y = rhs[0]
+/// matrix[1][0] * x + matrix[1][1] * y = rhs[1]
+/// ```
+pub fn solve_2x2<T: Integer>(matrix: [[T; 2]; 2], rhs: [T; 2]) -> Result<[T; 2], LinalgError> {
+    let [[a, b], [c, d]] = matrix;
+    let determinant = a * d - b * c;
+    if determinant == T::ZERO {
+        return Err(LinalgError::Singular);
+    }
+    let x = rhs[0] * d - b * rhs[1];
+    let y = a * rhs[1] - rhs[0] * c;
+    if x % determinant != T::ZERO || y % determinant != T::ZERO {
+        return Err(LinalgError::NoIntegerSolution);
+    }
+    Ok([x / determinant, y / determinant])
+}
+
+#[cfg(test)]
+mod rational_tests {
+    use super::*;
+
+    #[test]
+    fn normalised() {
+        assert_eq!(Rational::new(2, 4), Rational::new(1, 2));
+        assert_eq!(Rational::new(3, -6), Rational::new(-1, 2));
+        assert_eq!(Rational::new(-3, -6), Rational::new(1, 2));
+        assert_eq!(Rational::new(0, -5), Rational::ZERO);
+        assert_eq!(Rational::new(-3, -6).denom(), 2);
+    }
+
+    #[test]
+    fn arithmetic() {
+        let half = Rational::new(1, 2);
+        let third = Rational::new(1, 3);
+        assert_eq!(half + third, Rational::new(5, 6));
+        assert_eq!(half - third, Rational::new(1, 6));
+        assert_eq!(half * third, Rational::new(1, 6));
+        assert_eq!(half / third, Rational::new(3, 2));
+        assert_eq!(-half, Rational::new(-1, 2));
+        assert_eq!(half * Rational::ZERO, Rational::ZERO);
+        assert!(third < half);
+        assert!(-half < third);
+    }
+
+    #[test]
+    fn integers() {
+        assert_eq!(Rational::new(6, 3).to_integer(), Some(2));
+        assert_eq!(Rational::new(6, 4).to_integer(), None);
+        assert_eq!(Rational::new(6, 4).to_string(), "3/2");
+        assert_eq!(Rational::from(-7).to_string(), "-7");
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_denominator() {
+        Rational::new(1, 0);
+    }
+}
+
+#[cfg(test)]
+mod solve_tests {
+    use super::*;
+
+    #[test]
+    fn simple() {
+        let matrix = vec![vec![2, 1, -1], vec![-3, -1, 2], vec![-2, 1, 2]];
+        assert_eq!(solve_integer(&matrix, &[8, -11, -3]), Ok(vec![2, 3, -1]));
+    }
+
+    #[test]
+    fn fractions() {
+        let matrix = vec![vec![2, 0], vec![0, 3]];
+        assert_eq!(
+            solve(&matrix, &[1, 2]),
+            Ok(vec![Rational::new(1, 2), Rational::new(2, 3)])
+        );
+        assert_eq!(
+            solve_integer(&matrix, &[1, 2]),
+            Err(LinalgError::NoIntegerSolution)
+        );
+    }
+
+    #[test]
+    fn needs_swap() {
+        let matrix = vec![vec![0, 1], vec![1, 0]];
+        assert_eq!(solve_integer(&matrix, &[4, 5]), Ok(vec![5, 4]));
+    }
+
+    #[test]
+    fn singular() {
+        let matrix = vec![vec![1, 2], vec![2, 4]];
+        assert_eq!(solve(&matrix, &[3, 6]), Err(LinalgError::Singular));
+        assert_eq!(solve(&matrix, &[3, 7]), Err(LinalgError::Singular));
+    }
+
+    /// 2023 day 24 part 2 example.
+    ///
+    /// For every hailstone `(p - p_i) x (v - v_i) = 0`, subtracting that for 2 hailstones removes
+    /// the `p x v` part and leaves 3 linear equations.
+    #[test]
+    fn hailstones() {
+        let hail: [([i128; 3], [i128; 3]); 3] = [
+            ([19, 13, 30], [-2, 1, -2]),
+            ([18, 19, 22], [-1, -1, -2]),
+            ([20, 25, 34], [-2, -2, -4]),
+        ];
+        let cross = |a: [i128; 3], b: [i128; 3]| {
+            [
+                a[1] * b[2] - a[2] * b[1],
+                a[2] * b[0] - a[0] * b[2],
+                a[0] * b[1] - a[1] * b[0],
+            ]
+        };
+
+        let mut matrix = vec![];
+        let mut rhs = vec![];
+        for other in [1, 2] {
+            let (p_i, v_i) = hail[0];
+            let (p_j, v_j) = hail[other];
+            let d: Vec<i128> = (0..3).map(|i| v_i[i] - v_j[i]).collect();
+            let e: Vec<i128> = (0..3).map(|i| p_i[i] - p_j[i]).collect();
+            matrix.push(vec![0, d[2], -d[1], 0, -e[2], e[1]]);
+            matrix.push(vec![-d[2], 0, d[0], e[2], 0, -e[0]]);
+            matrix.push(vec![d[1], -d[0], 0, -e[1], e[0], 0]);
+            let (first, second) = (cross(p_i, v_i), cross(p_j, v_j));
+            rhs.extend((0..3).map(|i| first[i] - second[i]));
+        }
+
+        assert_eq!(solve_integer(&matrix, &rhs), Ok(vec![24, 13, 10, -3, 1, 2]));
+    }
+
+    #[test]
+    fn two_by_two() {
+        // 2024 day 13 example
+        assert_eq!(solve_2x2([[94, 22], [34, 67]], [8400, 5400]), Ok([80, 40]));
+        assert_eq!(
+            solve_2x2([[26, 67], [66, 21]], [12748, 12176]),
+            Err(LinalgError::NoIntegerSolution)
+        );
+        assert_eq!(
+            solve_2x2([[1, 2], [2, 4]], [1, 2]),
+            Err(LinalgError::Singular)
+        );
+    }
+}
@@ -0,0 +1,248 @@
+//! Reading the block letters that some puzzles draw on a screen.
+//!
+//! There are 2 fonts, a small one that is 6 pixels tall (mostly 4 wide) and a big one that is 10
+//! pixels tall and 6 wide. Which font is used is decided by the height of the lit part of the
+//! screen. Letters that aren't known get turned into `?`.
+
+use std::collections::HashSet;
+
+/// Character used for unknown letters.
+pub const UNKNOWN: char = '?';
+
+const SMALL_HEIGHT: usize = 6;
+const BIG_HEIGHT: usize = 10;
+
+#[rustfmt::skip]
+const SMALL_FONT: [(char, [&str; SMALL_HEIGHT]); 18] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', ["###", ".#.", ".#.", ".#.", ".#.", "###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', ["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+#[rustfmt::skip]
+const BIG_FONT: [(char, [&str; BIG_HEIGHT]); 15] = [
+    ('A', ["..##..", ".#..#.", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#"]),
+    ('B', ["#####.", "#....#", "#....#", "#....#", "#####.", "#....#", "#....#", "#....#", "#....#", "#####."]),
+    ('C', [".####.", "#....#", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#....#", ".####."]),
+    ('E', ["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('F', ["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('G', [".####.", "#....#", "#.....", "#.....", "#.....", "#..###", "#....#", "#....#", "#...##", ".###.#"]),
+    ('H', ["#....#", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#", "#....#"]),
+    ('J', ["...###", "....#.", "....#.", "....#.", "....#.", "....#.", "....#.", "#...#.", "#...#.", ".###.."]),
+    ('K', ["#....#", "#...#.", "#..#..", "#.#...", "##....", "##....", "#.#...", "#..#..", "#...#.", "#....#"]),
+    ('L', ["#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('N', ["#....#", "##...#", "##...#", "#.#..#", "#.#..#", "#..#.#", "#..#.#", "#...##", "#...##", "#....#"]),
+    ('P', ["#####.", "#....#", "#....#", "#....#", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('R', ["#####.", "#....#", "#....#", "#....#", "#####.", "#..#..", "#...#.", "#...#.", "#....#", "#....#"]),
+    ('X', ["#....#", "#....#", ".#..#.", ".#..#.", "..##..", "..##..", ".#..#.", ".#..#.", "#....#", "#....#"]),
+    ('Z', ["######", ".....#", ".....#", "....#.", "...#..", "..#...", ".#....", "#.....", "#.....", "######"]),
+];
+
+/// Reads the letters from a screen of pixels.
+///
+/// The screen can have any amount of empty space around the letters.
+pub fn recognise<R>(screen: &[R]) -> String
+where
+    R: AsRef<[bool]>,
+{
+    recognise_by(screen, |p| *p)
+}
+
+/// Same as [`recognise()`][recognise] but `is_lit` decides which tiles are turned on.
+pub fn recognise_by<R, T, F>(screen: &[R], is_lit: F) -> String
+where
+    R: AsRef<[T]>,
+    F: Fn(&T) -> bool,
+{
+    let points = screen.iter().enumerate().flat_map(|(y, row)| {
+        row.as_ref()
+            .iter()
+            .enumerate()
+            .filter(|(_, tile)| is_lit(tile))
+            .map(move |(x, _)| (x, y))
+    });
+    recognise_points(points)
+}
+
+/// Reads the letters from the (x, y) coordinates of the lit pixels.
+pub fn recognise_points<I>(points: I) -> String
+where
+    I: IntoIterator<Item = (usize, usize)>,
+{
+    let points: HashSet<(usize, usize)> = points.into_iter().collect();
+    let (Some(min_x), Some(max_x)) = (
+        points.iter().map(|p| p.0).min(),
+        points.iter().map(|p| p.0).max(),
+    ) else {
+        return String::new();
+    };
+    let min_y = points.iter().map(|p| p.1).min().unwrap();
+    let max_y = points.iter().map(|p| p.1).max().unwrap();
+
+    // Columns of the screen as strings, same format as the font
+    let columns: Vec<String> = (min_x..=max_x)
+        .map(|x| {
+            (min_y..=max_y)
+                .map(|y| if points.contains(&(x, y)) { '#' } else { '.' })
+                .collect()
+        })
+        .collect();
+
+    match max_y - min_y + 1 {
+        SMALL_HEIGHT => read_columns(&columns, &font_columns(&SMALL_FONT)),
+        BIG_HEIGHT => read_columns(&columns, &font_columns(&BIG_FONT)),
+        _ => UNKNOWN.to_string(),
+    }
+}
+
+/// Turns the rows of every letter into columns, widest letters first.
+fn font_columns<const H: usize>(font: &[(char, [&str; H])]) -> Vec<(char, Vec<String>)> {
+    let mut letters: Vec<(char, Vec<String>)> = font
+        .iter()
+        .map(|(c, rows)| {
+            let columns = (0..rows[0].len())
+                .map(|x| rows.iter().map(|r| r.as_bytes()[x] as char).collect())
+                .collect();
+            (*c, columns)
+        })
+        .collect();
+    letters.sort_by_key(|(_, columns)| std::cmp::Reverse(columns.len()));
+    letters
+}
+
+/// Goes over the columns and matches them with the letters.
+///
+/// Letters are usually split by an empty column but not always (`Y` is 5 wide in a 5 wide space)
+/// so this tries to match a letter at every non empty column.
+fn read_columns(columns: &[String], font: &[(char, Vec<String>)]) -> String {
+    let is_empty = |column: &String| !column.contains('#');
+    let mut result = String::new();
+    let mut x = 0;
+    while x < columns.len() {
+        if is_empty(&columns[x]) {
+            x += 1;
+            continue;
+        }
+        let found = font.iter().find(|(_, letter)| {
+            columns.len() - x >= letter.len() && columns[x..x + letter.len()] == letter[..]
+        });
+        match found {
+            Some((c, letter)) => {
+                result.push(*c);
+                x += letter.len();
+            }
+            None => {
+                result.push(UNKNOWN);
+                while x < columns.len() && !is_empty(&columns[x]) {
+                    x += 1;
+                }
+            }
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod ocr_tests {
+    use super::*;
+
+    /// Draws the text the same way the puzzles do, every letter starting 5 (or 8) pixels apart.
+    fn draw<const H: usize>(
+        text: &str,
+        font: &[(char, [&str; H])],
+        spacing: usize,
+    ) -> Vec<Vec<bool>> {
+        let mut screen = vec![vec![false; text.len() * spacing]; H];
+        for (i, c) in text.chars().enumerate() {
+            let (_, rows) = font.iter().find(|(l, _)| *l == c).unwrap();
+            for (y, row) in rows.iter().enumerate() {
+                for (x, p) in row.chars().enumerate() {
+                    screen[y][i * spacing + x] = p == '#';
+                }
+            }
+        }
+        screen
+    }
+
+    #[test]
+    fn small_letters() {
+        let text: String = SMALL_FONT.iter().map(|(c, _)| c).collect();
+        assert_eq!(recognise(&draw(&text, &SMALL_FONT, 5)), text);
+    }
+
+    #[test]
+    fn big_letters() {
+        let text: String = BIG_FONT.iter().map(|(c, _)| c).collect();
+        assert_eq!(recognise(&draw(&text, &BIG_FONT, 8)), text);
+    }
+
+    #[test]
+    fn touching_y() {
+        assert_eq!(recognise(&draw("YAY", &SMALL_FONT, 5)), "YAY");
+        assert_eq!(recognise(&draw("LYL", &SMALL_FONT, 5)), "LYL");
+    }
+
+    #[test]
+    fn chars_with_padding() {
+        let screen: Vec<Vec<char>> = [
+            "............",
+            ".#..#.####..",
+            ".#..#.#.....",
+            ".####.###...",
+            ".#..#.#.....",
+            ".#..#.#.....",
+            ".#..#.####..",
+            "............",
+        ]
+        .iter()
+        .map(|l| l.chars().collect())
+        .collect();
+        assert_eq!(recognise_by(&screen, |c| *c == '#'), "HE");
+    }
+
+    #[test]
+    fn points() {
+        let points = [
+            (0, 0),
+            (0, 1),
+            (0, 2),
+            (0, 3),
+            (0, 4),
+            (0, 5),
+            (1, 5),
+            (2, 5),
+            (3, 5),
+        ];
+        assert_eq!(recognise_points(points), "L");
+        assert_eq!(recognise_points([]), "");
+    }
+
+    #[test]
+    fn unknown() {
+        // The square from the 2021 day 13 example
+        let mut square = vec![vec![true; 5]; 5];
+        for row in square.iter_mut().take(4).skip(1) {
+            row[1..4].fill(false);
+        }
+        assert_eq!(recognise(&square), "?");
+
+        let mut screen = draw("HI", &SMALL_FONT, 5);
+        screen[0][0] = false;
+        assert_eq!(recognise(&screen), "?I");
+    }
+}
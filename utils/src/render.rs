@@ -0,0 +1,531 @@
+//! Turning grids into images for looking at simulations.
+//!
+//! Supports PPM and PNG for single frames and GIF for animations. Everything is written by hand
+//! to keep the crate free of dependencies so the PNGs aren't compressed.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+
+/// Red, green, blue
+pub type Rgb = [u8; 3];
+
+/// The background colour of the puzzle pages.
+pub const BACKGROUND: Rgb = [0x0f, 0x0f, 0x23];
+/// Colours for the tiles that don't have a special one.
+pub const PALETTE: [Rgb; 8] = [
+    [0xff, 0xff, 0x66],
+    [0x00, 0x99, 0x00],
+    [0xe6, 0x41, 0x0b],
+    [0x99, 0x99, 0xcc],
+    [0x00, 0xcc, 0xcc],
+    [0xcc, 0x66, 0xcc],
+    [0xff, 0x99, 0x33],
+    [0x66, 0x99, 0xff],
+];
+
+/// How a tile should look in the image.
+pub trait Colour {
+    fn colour(&self) -> Rgb;
+}
+
+impl Colour for Rgb {
+    fn colour(&self) -> Rgb {
+        *self
+    }
+}
+
+impl Colour for bool {
+    /// Light grey if true, background if false
+    fn colour(&self) -> Rgb {
+        if *self {
+            [0xcc, 0xcc, 0xcc]
+        } else {
+            BACKGROUND
+        }
+    }
+}
+
+impl Colour for char {
+    /// `.` and ` ` are the background, `#` is light grey and everything else gets a colour from
+    /// [`PALETTE`].
+    fn colour(&self) -> Rgb {
+        match self {
+            '.' | ' ' => BACKGROUND,
+            '#' => true.colour(),
+            c => PALETTE[*c as usize % PALETTE.len()],
+        }
+    }
+}
+
+impl Colour for u8 {
+    /// Shade of grey
+    fn colour(&self) -> Rgb {
+        [*self; 3]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RenderError {
+    /// GIFs can only have 256 colours.
+    TooManyColours,
+    /// All frames of a GIF need to be the same size.
+    FrameSize,
+    /// GIFs can't be bigger than 65535 pixels in any direction.
+    TooBig,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Image {
+    /// Creates an image filled with [`BACKGROUND`].
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![BACKGROUND; width * height],
+        }
+    }
+
+    /// Draws every tile as a `scale` by `scale` square.
+    pub fn from_grid<T: Colour>(grid: &[Vec<T>], scale: usize) -> Self {
+        let width = grid.first().map_or(0, |row| row.len());
+        let mut image = Self::new(width * scale, grid.len() * scale);
+        for (y, row) in grid.iter().enumerate() {
+            for (x, tile) in row.iter().enumerate() {
+                image.fill_rect(x * scale, y * scale, scale, scale, tile.colour());
+            }
+        }
+        image
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Rgb {
+        self.pixels[y * self.width + x]
+    }
+
+    /// Pixels outside of the image are ignored.
+    pub fn set(&mut self, x: usize, y: usize, colour: Rgb) {
+        if x < self.width && y < self.height {
+            self.pixels[y * self.width + x] = colour;
+        }
+    }
+
+    pub fn fill_rect(&mut self, x: usize, y: usize, width: usize, height: usize, colour: Rgb) {
+        for y in y..(y + height).min(self.height) {
+            for x in x..(x + width).min(self.width) {
+                self.pixels[y * self.width + x] = colour;
+            }
+        }
+    }
+
+    /// Binary PPM (P6)
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let mut data = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        data.extend(self.pixels.iter().flatten());
+        data
+    }
+
+    /// 8 bit RGB PNG without compression.
+    pub fn to_png(&self) -> Vec<u8> {
+        let mut data = b"\x89PNG\r\n\x1a\n".to_vec();
+
+        let mut header = vec![];
+        header.extend((self.width as u32).to_be_bytes());
+        header.extend((self.height as u32).to_be_bytes());
+        // Bit depth, colour type, compression, filter, interlace
+        header.extend([8, 2, 0, 0, 0]);
+        png_chunk(&mut data, b"IHDR", &header);
+
+        // Every row starts with the filter type, 0 is none
+        let mut raw = Vec::with_capacity(self.height * (self.width * 3 + 1));
+        for row in self.pixels.chunks(self.width.max(1)).take(self.height) {
+            raw.push(0);
+            raw.extend(row.iter().flatten());
+        }
+        png_chunk(&mut data, b"IDAT", &zlib_stored(&raw));
+        png_chunk(&mut data, b"IEND", &[]);
+        data
+    }
+
+    pub fn write_ppm(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        File::create(path)?.write_all(&self.to_ppm())
+    }
+
+    pub fn write_png(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        File::create(path)?.write_all(&self.to_png())
+    }
+}
+
+/// Collects frames and turns them into an animated GIF that loops forever.
+#[derive(Debug, Clone, Default)]
+pub struct Gif {
+    frames: Vec<Image>,
+    /// In hundredths of a second
+    delay: u16,
+}
+
+impl Gif {
+    /// `delay` is how long every frame is shown in hundredths of a second.
+    pub fn new(delay: u16) -> Self {
+        Self {
+            frames: vec![],
+            delay,
+        }
+    }
+
+    pub fn push(&mut self, frame: Image) {
+        self.frames.push(frame);
+    }
+
+    /// Same as [`push()`][Gif::push] but makes the image from the grid.
+    pub fn push_grid<T: Colour>(&mut self, grid: &[Vec<T>], scale: usize) {
+        self.push(Image::from_grid(grid, scale));
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, RenderError> {
+        let (width, height) = self.frames.first().map_or((1, 1), |f| (f.width, f.height));
+        if self
+            .frames
+            .iter()
+            .any(|f| f.width != width || f.height != height)
+        {
+            return Err(RenderError::FrameSize);
+        }
+        let (Ok(w), Ok(h)) = (u16::try_from(width), u16::try_from(height)) else {
+            return Err(RenderError::TooBig);
+        };
+
+        // One palette for all frames
+        let mut palette: Vec<Rgb> = vec![];
+        let mut indexes: HashMap<Rgb, u8> = HashMap::new();
+        for pixel in self.frames.iter().flat_map(|f| &f.pixels) {
+            if !indexes.contains_key(pixel) {
+                let index = u8::try_from(palette.len()).map_err(|_| RenderError::TooManyColours)?;
+                indexes.insert(*pixel, index);
+                palette.push(*pixel);
+            }
+        }
+        // The palette needs 2^n colours, at least 4 because of the LZW minimum code size
+        let bits = (palette.len().max(4).next_power_of_two().trailing_zeros()).max(2);
+        palette.resize(1 << bits, [0, 0, 0]);
+
+        let mut data = b"GIF89a".to_vec();
+        data.extend(w.to_le_bytes());
+        data.extend(h.to_le_bytes());
+        // Global colour table with 8 bit colour resolution, background colour 0, no aspect ratio
+        data.extend([0xF0 | (bits as u8 - 1), 0, 0]);
+        data.extend(palette.iter().flatten());
+        // Loop forever
+        data.extend(b"\x21\xFF\x0BNETSCAPE2.0\x03\x01\x00\x00\x00");
+
+        for frame in &self.frames {
+            // Graphic control extension with the delay
+            data.extend([0x21, 0xF9, 0x04, 0x00]);
+            data.extend(self.delay.to_le_bytes());
+            data.extend([0x00, 0x00]);
+            // Image descriptor covering the whole screen
+            data.push(0x2C);
+            data.extend([0, 0, 0, 0]);
+            data.extend(w.to_le_bytes());
+            data.extend(h.to_le_bytes());
+            data.push(0);
+
+            let pixels: Vec<u8> = frame.pixels.iter().map(|p| indexes[p]).collect();
+            data.push(bits as u8);
+            for block in lzw_encode(&pixels, bits).chunks(255) {
+                data.push(block.len() as u8);
+                data.extend(block);
+            }
+            data.push(0);
+        }
+        data.push(0x3B);
+        Ok(data)
+    }
+
+    pub fn write(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let data = self
+            .to_bytes()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("{e:?}")))?;
+        File::create(path)?.write_all(&data)
+    }
+}
+
+fn png_chunk(data: &mut Vec<u8>, kind: &[u8; 4], content: &[u8]) {
+    data.extend((content.len() as u32).to_be_bytes());
+    let start = data.len();
+    data.extend(kind);
+    data.extend(content);
+    let crc = crc32(&data[start..]);
+    data.extend(crc.to_be_bytes());
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = u32::MAX;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                0xEDB8_8320 ^ (crc >> 1)
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+/// Zlib stream that only uses stored (not compressed) deflate blocks.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x78, 0x01];
+    let mut blocks = data.chunks(u16::MAX as usize).peekable();
+    if blocks.peek().is_none() {
+        out.extend([1, 0, 0, 0xFF, 0xFF]);
+    }
+    while let Some(block) = blocks.next() {
+        out.push(blocks.peek().is_none() as u8);
+        let len = block.len() as u16;
+        out.extend(len.to_le_bytes());
+        out.extend((!len).to_le_bytes());
+        out.extend(block);
+    }
+
+    let (mut a, mut b) = (1_u32, 0_u32);
+    for byte in data {
+        a = (a + *byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    out.extend((b << 16 | a).to_be_bytes());
+    out
+}
+
+/// GIF flavoured LZW, codes are packed starting with the least significant bit.
+fn lzw_encode(indexes: &[u8], min_size: u32) -> Vec<u8> {
+    let clear = 1_u32 << min_size;
+    let end = clear + 1;
+
+    let mut out = vec![];
+    let mut buffer = 0_u32;
+    let mut buffered = 0;
+    let mut emit = |code: u32, size: u32, out: &mut Vec<u8>| {
+        buffer |= code << buffered;
+        buffered += size;
+        while buffered >= 8 {
+            out.push(buffer as u8);
+            buffer >>= 8;
+            buffered -= 8;
+        }
+    };
+
+    let mut table: HashMap<(u32, u8), u32> = HashMap::new();
+    let mut next_code = end + 1;
+    let mut size = min_size + 1;
+    emit(clear, size, &mut out);
+
+    let mut current: Option<u32> = None;
+    for &index in indexes {
+        let Some(prefix) = current else {
+            current = Some(index as u32);
+            continue;
+        };
+        if let Some(&code) = table.get(&(prefix, index)) {
+            current = Some(code);
+            continue;
+        }
+        emit(prefix, size, &mut out);
+        if next_code < 4096 {
+            table.insert((prefix, index), next_code);
+            // The decoder makes its table one code behind so it grows the size one code later
+            if next_code == 1 << size {
+                size += 1;
+            }
+            next_code += 1;
+        } else {
+            emit(clear, size, &mut out);
+            table.clear();
+            next_code = end + 1;
+            size = min_size + 1;
+        }
+        current = Some(index as u32);
+    }
+    if let Some(code) = current {
+        emit(code, size, &mut out);
+    }
+    emit(end, size, &mut out);
+    emit(0, 7, &mut out);
+    out
+}
+
+#[cfg(test)]
+mod render_tests {
+    use super::*;
+
+    fn grid() -> Vec<Vec<char>> {
+        ["#..", ".O.", "..#"]
+            .iter()
+            .map(|l| l.chars().collect())
+            .collect()
+    }
+
+    #[test]
+    fn from_grid() {
+        let image = Image::from_grid(&grid(), 2);
+        assert_eq!((image.width(), image.height()), (6, 6));
+        assert_eq!(image.get(1, 1), '#'.colour());
+        assert_eq!(image.get(2, 0), BACKGROUND);
+        assert_eq!(image.get(3, 3), 'O'.colour());
+        assert_ne!('O'.colour(), BACKGROUND);
+    }
+
+    #[test]
+    fn ppm() {
+        let image = Image::from_grid(&[vec![true, false]], 1);
+        let mut expected = b"P6\n2 1\n255\n".to_vec();
+        expected.extend([0xcc, 0xcc, 0xcc]);
+        expected.extend(BACKGROUND);
+        assert_eq!(image.to_ppm(), expected);
+    }
+
+    #[test]
+    fn checksums() {
+        assert_eq!(crc32(b"IEND"), 0xAE42_6082);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        // Adler-32 of "Wikipedia" is 0x11E60398
+        assert!(zlib_stored(b"Wikipedia").ends_with(&[0x11, 0xE6, 0x03, 0x98]));
+    }
+
+    #[test]
+    fn png() {
+        let png = Image::from_grid(&grid(), 1).to_png();
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR"));
+        assert!(png.ends_with(b"\x00\x00\x00\x00IEND\xae\x42\x60\x82"));
+
+        // 3 rows of a filter byte and 3 pixels
+        let data = &zlib_stored(&[0; 30]);
+        assert_eq!(&data[..7], [0x78, 0x01, 0x01, 30, 0, !30, 0xFF]);
+    }
+
+    /// Decodes GIF LZW to check the encoder.
+    fn lzw_decode(data: &[u8], min_size: u32) -> Vec<u8> {
+        let clear = 1 << min_size;
+        let end = clear + 1;
+        let mut table: Vec<Vec<u8>> = vec![];
+        let reset = |table: &mut Vec<Vec<u8>>| {
+            *table = (0..clear).map(|i| vec![i as u8]).collect();
+            table.push(vec![]);
+            table.push(vec![]);
+        };
+        reset(&mut table);
+        let mut size = min_size + 1;
+        let mut out = vec![];
+        let mut previous: Option<usize> = None;
+        let mut position = 0;
+        loop {
+            let mut code = 0;
+            for i in 0..size as usize {
+                let bit = data[(position + i) / 8] >> ((position + i) % 8) & 1;
+                code |= (bit as usize) << i;
+            }
+            position += size as usize;
+            if code == clear {
+                reset(&mut table);
+                size = min_size + 1;
+                previous = None;
+                continue;
+            }
+            if code == end {
+                return out;
+            }
+            let entry = match (table.get(code), previous) {
+                (Some(e), _) => e.clone(),
+                (None, Some(p)) => {
+                    let mut e = table[p].clone();
+                    e.push(table[p][0]);
+                    e
+                }
+                (None, None) => panic!("Invalid code"),
+            };
+            if let Some(p) = previous {
+                if table.len() < 4096 {
+                    let mut new = table[p].clone();
+                    new.push(entry[0]);
+                    table.push(new);
+                }
+            }
+            if table.len() == 1 << size && size < 12 {
+                size += 1;
+            }
+            out.extend(&entry);
+            previous = Some(code);
+        }
+    }
+
+    #[test]
+    fn lzw() {
+        let mut seed: u32 = 7;
+        let mut random = || {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            seed
+        };
+        let inputs: Vec<(Vec<u8>, u32)> = vec![
+            (vec![], 2),
+            (vec![1], 2),
+            (vec![0; 10_000], 2),
+            ((0..20_000).map(|_| (random() % 4) as u8).collect(), 2),
+            ((0..20_000).map(|_| (random() % 200) as u8).collect(), 8),
+            ((0..20_000).map(|i| (i % 7) as u8).collect(), 3),
+        ];
+        for (input, size) in inputs {
+            assert_eq!(lzw_decode(&lzw_encode(&input, size), size), input);
+        }
+    }
+
+    #[test]
+    fn gif() {
+        let mut gif = Gif::new(10);
+        let mut grid = grid();
+        for i in 0..3 {
+            grid[1][i] = 'O';
+            gif.push_grid(&grid, 2);
+        }
+        assert_eq!(gif.len(), 3);
+        let data = gif.to_bytes().unwrap();
+        assert!(data.starts_with(b"GIF89a\x06\x00\x06\x00"));
+        assert_eq!(data.last(), Some(&0x3B));
+        assert_eq!(data.windows(3).filter(|w| w == b"\x21\xF9\x04").count(), 3);
+
+        gif.push(Image::new(1, 1));
+        assert_eq!(gif.to_bytes(), Err(RenderError::FrameSize));
+
+        let mut colours = Image::new(300, 1);
+        for x in 0..300 {
+            colours.set(x, 0, [x as u8, (x / 256) as u8, 0]);
+        }
+        let mut gif = Gif::new(1);
+        gif.push(colours);
+        assert_eq!(gif.to_bytes(), Err(RenderError::TooManyColours));
+    }
+}
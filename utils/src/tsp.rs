@@ -0,0 +1,188 @@
+//! Travelling salesman for small complete graphs.
+//!
+//! Uses the Held–Karp bitmask DP which is `O(n² 2ⁿ)` instead of trying every permutation, good
+//! enough for about 20 nodes.
+
+use std::ops::Add;
+
+/// Looking for the cheapest or the most expensive route.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Goal {
+    Min,
+    Max,
+}
+
+/// What kind of route to find.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Route {
+    /// Visit every node once and stop at the last one.
+    Path,
+    /// Visit every node once and go back to the first one.
+    Tour,
+}
+
+impl Goal {
+    fn is_better<T: Ord>(&self, new: &T, old: &T) -> bool {
+        match self {
+            Goal::Min => new < old,
+            Goal::Max => new > old,
+        }
+    }
+}
+
+/// Finds the best route that visits every node exactly once.
+///
+/// `distances[from][to]` is the cost of going from `from` to `to`, it doesn't need to be the same
+/// both ways. If `start` is given the route has to start there, otherwise any node can be the
+/// start (for a [`Route::Tour`] that doesn't matter). Returns the cost and the order of the
+/// nodes, `None` if there are no nodes.
+pub fn held_karp<T>(
+    distances: &[Vec<T>],
+    goal: Goal,
+    route: Route,
+    start: Option<usize>,
+) -> Option<(T, Vec<usize>)>
+where
+    T: Copy + Ord + Add<Output = T> + Default,
+{
+    let size = distances.len();
+    if size == 0 {
+        return None;
+    }
+    assert!(
+        size < usize::BITS as usize,
+        "Too many nodes for the bitmask"
+    );
+    assert!(
+        distances.iter().all(|row| row.len() == size),
+        "Distances need to be a square matrix"
+    );
+
+    // Every node is a valid start for a path, for a tour the start doesn't change anything
+    let starts: Vec<usize> = match (start, route) {
+        (Some(s), _) => vec![s],
+        (None, Route::Tour) => vec![0],
+        (None, Route::Path) => (0..size).collect(),
+    };
+
+    // best[mask][last] = (cost, previous node) of the best route that visits the nodes in mask
+    // and ends at last
+    let full = (1 << size) - 1;
+    let mut best: Vec<Vec<Option<(T, usize)>>> = vec![vec![None; size]; 1 << size];
+    for &s in &starts {
+        best[1 << s][s] = Some((T::default(), s));
+    }
+
+    for mask in 1..=full {
+        for last in 0..size {
+            let Some((cost, _)) = best[mask][last] else {
+                continue;
+            };
+            for next in 0..size {
+                if mask & (1 << next) != 0 {
+                    continue;
+                }
+                let new_cost = cost + distances[last][next];
+                let slot = &mut best[mask | (1 << next)][next];
+                match slot {
+                    Some((old, _)) if !goal.is_better(&new_cost, old) => (),
+                    _ => *slot = Some((new_cost, last)),
+                }
+            }
+        }
+    }
+
+    let mut result: Option<(T, usize)> = None;
+    for last in 0..size {
+        let Some((mut cost, _)) = best[full][last] else {
+            continue;
+        };
+        if route == Route::Tour {
+            cost = cost + distances[last][starts[0]];
+        }
+        match result {
+            Some((old, _)) if !goal.is_better(&cost, &old) => (),
+            _ => result = Some((cost, last)),
+        }
+    }
+    let (cost, mut cur) = result?;
+
+    // Walk back through the previous nodes to get the order
+    let mut order = vec![cur];
+    let mut mask = full;
+    while mask.count_ones() > 1 {
+        let (_, previous) = best[mask][cur].unwrap();
+        mask &= !(1 << cur);
+        cur = previous;
+        order.push(cur);
+    }
+    order.reverse();
+    Some((cost, order))
+}
+
+#[cfg(test)]
+mod tsp_tests {
+    use super::*;
+
+    /// 2015 day 9 example: London, Dublin, Belfast
+    fn cities() -> Vec<Vec<usize>> {
+        vec![vec![0, 464, 518], vec![464, 0, 141], vec![518, 141, 0]]
+    }
+
+    #[test]
+    fn shortest_path() {
+        let (cost, order) = held_karp(&cities(), Goal::Min, Route::Path, None).unwrap();
+        assert_eq!(cost, 605);
+        assert!(order == [0, 1, 2] || order == [2, 1, 0]);
+    }
+
+    #[test]
+    fn longest_path() {
+        let (cost, order) = held_karp(&cities(), Goal::Max, Route::Path, None).unwrap();
+        assert_eq!(cost, 982);
+        assert!(order == [1, 0, 2] || order == [2, 0, 1]);
+    }
+
+    #[test]
+    fn fixed_start() {
+        let (cost, order) = held_karp(&cities(), Goal::Min, Route::Path, Some(0)).unwrap();
+        assert_eq!(cost, 605);
+        assert_eq!(order, [0, 1, 2]);
+        let (cost, order) = held_karp(&cities(), Goal::Min, Route::Path, Some(1)).unwrap();
+        assert_eq!(cost, 141 + 518);
+        assert_eq!(order, [1, 2, 0]);
+    }
+
+    #[test]
+    fn tour() {
+        let (cost, order) = held_karp(&cities(), Goal::Min, Route::Tour, Some(2)).unwrap();
+        assert_eq!(cost, 464 + 518 + 141);
+        assert_eq!(order.len(), 3);
+        assert_eq!(order[0], 2);
+    }
+
+    #[test]
+    fn negative_and_asymmetric() {
+        let distances = vec![vec![0, -5, 3], vec![1, 0, -2], vec![4, 10, 0]];
+        // 0 -> 1 -> 2 -> 0
+        assert_eq!(
+            held_karp(&distances, Goal::Min, Route::Tour, None).map(|r| r.0),
+            Some(-3)
+        );
+        // 0 -> 2 -> 1
+        assert_eq!(
+            held_karp(&distances, Goal::Max, Route::Path, None),
+            Some((13, vec![0, 2, 1]))
+        );
+    }
+
+    #[test]
+    fn empty_and_single() {
+        let empty: Vec<Vec<usize>> = vec![];
+        assert_eq!(held_karp(&empty, Goal::Min, Route::Path, None), None);
+        assert_eq!(
+            held_karp(&[vec![0]], Goal::Min, Route::Tour, None),
+            Some((0, vec![0]))
+        );
+    }
+}
@@ -0,0 +1,375 @@
+//! A small register machine for the puzzles that come with their own assembly language.
+//!
+//! Every puzzle only has to define its instructions and implement [`Instruction`] for them, the
+//! [`Machine`] takes care of the registers, the program counter, the output, breakpoints and
+//! tracing.
+
+use std::collections::HashSet;
+use std::fmt::Debug;
+use std::ops::{Index, IndexMut};
+
+/// What the machine should do after an instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Flow {
+    /// Go to the next instruction.
+    Next,
+    /// Jump relative to the current instruction.
+    Jump(isize),
+    /// Jump to the instruction at the index.
+    Goto(usize),
+    /// Stop the program.
+    Halt,
+}
+
+/// Why the machine stopped running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Stop {
+    /// The program counter left the program or an instruction returned [`Flow::Halt`].
+    Halted,
+    /// Reached a breakpoint, the instruction at it wasn't executed yet.
+    Breakpoint(usize),
+    /// The condition given to [`Machine::run_until()`] was true.
+    Condition,
+}
+
+pub trait Instruction: Clone + Debug {
+    /// Executes the instruction and says where to go next.
+    ///
+    /// Gets the whole machine so instructions can also change the program.
+    fn execute(&self, machine: &mut Machine<Self>) -> Flow;
+}
+
+/// The registers of the machine, all start at 0.
+///
+/// Can be indexed with a number or with a letter, `a` and `A` are both register 0.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Registers(Vec<i64>);
+
+impl Registers {
+    pub fn new(count: usize) -> Self {
+        Self(vec![0; count])
+    }
+
+    /// Turns the name of a register into its index.
+    pub fn index_of(name: char) -> Option<usize> {
+        match name {
+            'a'..='z' => Some(name as usize - 'a' as usize),
+            'A'..='Z' => Some(name as usize - 'A' as usize),
+            _ => None,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn as_slice(&self) -> &[i64] {
+        &self.0
+    }
+}
+
+impl Index<usize> for Registers {
+    type Output = i64;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.0[index]
+    }
+}
+
+impl IndexMut<usize> for Registers {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.0[index]
+    }
+}
+
+impl Index<char> for Registers {
+    type Output = i64;
+
+    fn index(&self, name: char) -> &Self::Output {
+        let index = Self::index_of(name).unwrap_or_else(|| panic!("Invalid register {name}"));
+        &self.0[index]
+    }
+}
+
+impl IndexMut<char> for Registers {
+    fn index_mut(&mut self, name: char) -> &mut Self::Output {
+        let index = Self::index_of(name).unwrap_or_else(|| panic!("Invalid register {name}"));
+        &mut self.0[index]
+    }
+}
+
+/// A single executed instruction, saved when tracing is turned on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceEntry<I> {
+    pub pc: usize,
+    pub instruction: I,
+    /// The registers after the instruction was executed.
+    pub registers: Registers,
+}
+
+#[derive(Debug, Clone)]
+pub struct Machine<I> {
+    pub registers: Registers,
+    /// Everything the program has outputted.
+    pub output: Vec<i64>,
+    program: Vec<I>,
+    pc: usize,
+    halted: bool,
+    steps: usize,
+    breakpoints: HashSet<usize>,
+    trace: Option<Vec<TraceEntry<I>>>,
+}
+
+impl<I: Instruction> Machine<I> {
+    pub fn new(program: Vec<I>, registers: usize) -> Self {
+        Self {
+            registers: Registers::new(registers),
+            output: vec![],
+            program,
+            pc: 0,
+            halted: false,
+            steps: 0,
+            breakpoints: HashSet::new(),
+            trace: None,
+        }
+    }
+
+    pub fn program(&self) -> &[I] {
+        &self.program
+    }
+
+    /// Mutable access to the program, for the instructions that change other instructions.
+    pub fn program_mut(&mut self) -> &mut [I] {
+        &mut self.program
+    }
+
+    /// The index of the next instruction.
+    pub fn pc(&self) -> usize {
+        self.pc
+    }
+
+    pub fn set_pc(&mut self, pc: usize) {
+        self.pc = pc;
+        self.halted = pc >= self.program.len();
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.halted
+    }
+
+    /// How many instructions have been executed.
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Puts the machine back at the start of the program with empty registers and output.
+    ///
+    /// Breakpoints and tracing stay as they were but the trace gets cleared.
+    pub fn reset(&mut self) {
+        self.registers = Registers::new(self.registers.len());
+        self.output.clear();
+        self.pc = 0;
+        self.halted = false;
+        self.steps = 0;
+        if let Some(trace) = &mut self.trace {
+            trace.clear();
+        }
+    }
+
+    pub fn add_breakpoint(&mut self, pc: usize) {
+        self.breakpoints.insert(pc);
+    }
+
+    pub fn remove_breakpoint(&mut self, pc: usize) -> bool {
+        self.breakpoints.remove(&pc)
+    }
+
+    /// Starts saving every executed instruction.
+    pub fn enable_trace(&mut self) {
+        self.trace.get_or_insert_with(Vec::new);
+    }
+
+    /// Stops tracing and returns what was traced so far.
+    pub fn disable_trace(&mut self) -> Vec<TraceEntry<I>> {
+        self.trace.take().unwrap_or_default()
+    }
+
+    pub fn trace(&self) -> &[TraceEntry<I>] {
+        self.trace.as_deref().unwrap_or_default()
+    }
+
+    /// Executes a single instruction, returns `false` if the machine is halted.
+    pub fn step(&mut self) -> bool {
+        if self.halted || self.pc >= self.program.len() {
+            self.halted = true;
+            return false;
+        }
+        let pc = self.pc;
+        let instruction = self.program[pc].clone();
+        let flow = instruction.execute(self);
+        self.steps += 1;
+        if let Some(trace) = &mut self.trace {
+            trace.push(TraceEntry {
+                pc,
+                instruction,
+                registers: self.registers.clone(),
+            });
+        }
+
+        let next = match flow {
+            Flow::Next => pc.checked_add(1),
+            Flow::Jump(offset) => pc.checked_add_signed(offset),
+            Flow::Goto(target) => Some(target),
+            Flow::Halt => None,
+        };
+        match next {
+            Some(next) if next < self.program.len() => self.pc = next,
+            _ => self.halted = true,
+        }
+        true
+    }
+
+    /// Runs until the program halts or a breakpoint is reached.
+    ///
+    /// The current instruction is always executed so running again continues past the breakpoint.
+    pub fn run(&mut self) -> Stop {
+        self.run_until(|_| false)
+    }
+
+    /// Same as [`run()`][Machine::run] but also stops when `stop` returns true after an
+    /// instruction.
+    pub fn run_until<F>(&mut self, mut stop: F) -> Stop
+    where
+        F: FnMut(&Self) -> bool,
+    {
+        if !self.step() {
+            return Stop::Halted;
+        }
+        loop {
+            if stop(self) {
+                return Stop::Condition;
+            }
+            if self.halted {
+                return Stop::Halted;
+            }
+            if self.breakpoints.contains(&self.pc) {
+                return Stop::Breakpoint(self.pc);
+            }
+            self.step();
+        }
+    }
+}
+
+#[cfg(test)]
+mod vm_tests {
+    use super::*;
+
+    /// Some of the 2015 day 23 instructions
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum Op {
+        Tpl(char),
+        Inc(char),
+        Jmp(isize),
+        Jio(char, isize),
+        Out(char),
+        Nop,
+    }
+
+    impl Instruction for Op {
+        fn execute(&self, machine: &mut Machine<Self>) -> Flow {
+            let reg = &mut machine.registers;
+            match *self {
+                Op::Tpl(r) => reg[r] *= 3,
+                Op::Inc(r) => reg[r] += 1,
+                Op::Jmp(offset) => return Flow::Jump(offset),
+                Op::Jio(r, offset) if reg[r] == 1 => return Flow::Jump(offset),
+                Op::Out(r) => machine.output.push(reg[r]),
+                Op::Jio(_, _) | Op::Nop => (),
+            }
+            Flow::Next
+        }
+    }
+
+    fn example() -> Machine<Op> {
+        Machine::new(
+            vec![Op::Inc('a'), Op::Jio('a', 2), Op::Tpl('a'), Op::Inc('a')],
+            2,
+        )
+    }
+
+    #[test]
+    fn run() {
+        let mut machine = example();
+        assert_eq!(machine.run(), Stop::Halted);
+        assert_eq!(machine.registers['a'], 2);
+        assert_eq!(machine.steps(), 3);
+        assert!(machine.is_halted());
+        assert!(!machine.step());
+        assert_eq!(machine.run(), Stop::Halted);
+
+        machine.reset();
+        machine.registers['a'] = 1;
+        machine.run();
+        assert_eq!(machine.registers['a'], 7);
+    }
+
+    #[test]
+    fn breakpoints() {
+        let mut machine = example();
+        machine.add_breakpoint(3);
+        assert_eq!(machine.run(), Stop::Breakpoint(3));
+        assert_eq!(machine.registers['a'], 1);
+        assert_eq!(machine.pc(), 3);
+        assert_eq!(machine.run(), Stop::Halted);
+        assert_eq!(machine.registers['a'], 2);
+        assert!(machine.remove_breakpoint(3));
+        assert!(!machine.remove_breakpoint(3));
+    }
+
+    #[test]
+    fn trace() {
+        let mut machine = example();
+        machine.enable_trace();
+        machine.run();
+        let pcs: Vec<usize> = machine.trace().iter().map(|t| t.pc).collect();
+        assert_eq!(pcs, [0, 1, 3]);
+        let trace = machine.disable_trace();
+        assert_eq!(trace[0].instruction, Op::Inc('a'));
+        assert_eq!(trace[2].registers.as_slice(), [2, 0]);
+        assert!(machine.trace().is_empty());
+    }
+
+    #[test]
+    fn output_and_condition() {
+        // Counts up forever
+        let program = vec![Op::Inc('b'), Op::Out('b'), Op::Jmp(-2)];
+        let mut machine = Machine::new(program, 2);
+        assert_eq!(machine.run_until(|m| m.output.len() == 5), Stop::Condition);
+        assert_eq!(machine.output, [1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn jumps_out() {
+        let mut machine = Machine::new(vec![Op::Nop, Op::Jmp(-5), Op::Nop], 1);
+        assert_eq!(machine.run(), Stop::Halted);
+        assert_eq!(machine.steps(), 2);
+        machine.set_pc(2);
+        assert!(!machine.is_halted());
+        assert!(machine.step());
+        assert!(machine.is_halted());
+    }
+
+    #[test]
+    fn register_names() {
+        assert_eq!(Registers::index_of('a'), Some(0));
+        assert_eq!(Registers::index_of('C'), Some(2));
+        assert_eq!(Registers::index_of('1'), None);
+        let mut registers = Registers::new(3);
+        registers['B'] = 5;
+        assert_eq!(registers[1], 5);
+    }
+}